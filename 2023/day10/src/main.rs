@@ -1,5 +1,4 @@
-use rayon::prelude::*;
-use std::collections::HashSet;
+use aoc_common::LatticePolygon;
 
 fn main() {
     let input = include_str!("../input.txt");
@@ -63,87 +62,6 @@ impl Grid {
     fn loop_length(&self) -> usize {
         self.get_loop().len()
     }
-
-    fn non_loop(&self) -> Vec<(usize, usize)> {
-        let loop_ = self.get_loop();
-        let loop_: HashSet<_> = loop_.iter().collect();
-
-        let height = self.paths.len();
-        let width = self.paths[0].len();
-
-        let mut result = vec![];
-
-        for y in 0..height {
-            for x in 0..width {
-                if !loop_.contains(&(x, y)) {
-                    result.push((x, y));
-                }
-            }
-        }
-
-        result
-    }
-}
-
-fn does_enclose(loop_: &[(usize, usize)], (x, y): (usize, usize)) -> bool {
-    let mut hit_directions = vec![];
-
-    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-    enum Direction {
-        Up,
-        Down,
-        Left,
-        Right,
-    }
-
-    for &(loop_x, loop_y) in loop_ {
-        if loop_x == x {
-            if loop_y > y {
-                hit_directions.push(Direction::Down);
-            } else {
-                hit_directions.push(Direction::Up);
-            }
-        }
-
-        if loop_y == y {
-            if loop_x > x {
-                hit_directions.push(Direction::Right);
-            } else {
-                hit_directions.push(Direction::Left);
-            }
-        }
-    }
-
-    let mut winding_number = 0;
-
-    if hit_directions.is_empty() {
-        return false;
-    }
-
-    hit_directions.push(hit_directions[0]);
-    let mut previous_direction = hit_directions[0];
-
-    for &hit_direction in &hit_directions {
-        if hit_direction == previous_direction {
-            continue;
-        }
-
-        winding_number += match (previous_direction, hit_direction) {
-            (Direction::Up, Direction::Left) => -1,
-            (Direction::Up, Direction::Right) => 1,
-            (Direction::Down, Direction::Left) => 1,
-            (Direction::Down, Direction::Right) => -1,
-            (Direction::Left, Direction::Up) => 1,
-            (Direction::Left, Direction::Down) => -1,
-            (Direction::Right, Direction::Up) => -1,
-            (Direction::Right, Direction::Down) => 1,
-            _ => panic!("Invalid directions {previous_direction:?} {hit_direction:?}"),
-        };
-
-        previous_direction = hit_direction;
-    }
-
-    winding_number % 4 == 0 && winding_number != 0
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
@@ -198,11 +116,15 @@ impl Connection {
 }
 
 fn enclosed_area(grid: &Grid) -> usize {
-    let enclosed_loop = grid.get_loop();
-    grid.non_loop()
-        .par_iter()
-        .filter(|&&pos| does_enclose(&enclosed_loop, pos))
-        .count()
+    let polygon = LatticePolygon::new(
+        grid.get_loop()
+            .into_iter()
+            // grid indices are below isize::MAX, so they always fit in an i64
+            .map(|(x, y)| (x as i64, y as i64))
+            .collect(),
+    );
+
+    polygon.interior_points() as usize
 }
 
 #[test]
@@ -1,5 +1,6 @@
+use aoc_common::Nfa;
 use rayon::prelude::*;
-use std::{collections::HashMap, fmt, iter};
+use std::{fmt, iter};
 
 fn main() {
     let input = include_str!("../input.txt");
@@ -37,82 +38,57 @@ impl fmt::Debug for SpringInformation {
     }
 }
 
-fn working_arrangements<'a>(
-    spring_information: &'a [SpringInformation],
-    contiguous_damaged: &'a [usize],
-    arrangement_cache: &mut HashMap<(&'a [SpringInformation], &'a [usize]), usize>,
-) -> usize {
-    if let Some(&arrangements) = arrangement_cache.get(&(spring_information, contiguous_damaged)) {
-        return arrangements;
-    }
-
-    if contiguous_damaged.is_empty() {
-        return if spring_information.contains(&SpringInformation::Damaged) {
-            0
-        } else {
-            1
-        };
+impl SpringInformation {
+    /// The springs which could be in this position
+    fn possible_springs(self) -> &'static [u8] {
+        match self {
+            Self::Operational => b".",
+            Self::Damaged => b"#",
+            Self::MissingInformation => b".#",
+        }
     }
+}
 
-    let mut start_position = 0;
-    let mut total_arragements = 0;
-
-    let number_damaged = contiguous_damaged[0];
-
-    if spring_information.len() < number_damaged {
-        // not enough space left for the number of damaged ones
-        return 0;
+/// Builds an automaton which matches rows of springs with the given runs of
+/// damaged springs. Each state is a position in the pattern `.#..#.` with one
+/// `#` for every damaged spring, and a `.` between runs and at either end.
+fn spring_automaton(contiguous_damaged: &[usize]) -> Nfa {
+    let mut pattern = vec![b'.'];
+    for &number_damaged in contiguous_damaged {
+        pattern.extend(iter::repeat_n(b'#', number_damaged));
+        pattern.push(b'.');
     }
 
-    // try to fit the first value into the spring information from start_position
-    while start_position <= spring_information.len() - number_damaged {
-        if spring_information[start_position..(start_position + number_damaged)]
-            .contains(&SpringInformation::Operational)
-        {
-            if spring_information[start_position] == SpringInformation::Damaged {
-                // this one was forced
-                break;
-            }
-
-            start_position += 1;
-            continue;
+    let mut automaton = Nfa::new(pattern.len());
+    for (state, &spring) in pattern.iter().enumerate() {
+        // any number of operational springs can go in a gap
+        if spring == b'.' {
+            automaton.add_transition(state, b'.', state);
         }
 
-        if spring_information.get(start_position + number_damaged)
-            == Some(&SpringInformation::Damaged)
-        {
-            // we have more than number_damaged in a row if the first one here was forced to be damage from the start
-            if spring_information[start_position] == SpringInformation::Damaged {
-                break;
-            } else {
-                start_position += 1;
-            }
-        } else {
-            let remaining_information =
-                if spring_information.len() == start_position + number_damaged {
-                    &[]
-                } else {
-                    &spring_information[start_position + number_damaged + 1..]
-                };
-
-            total_arragements += working_arrangements(
-                remaining_information,
-                &contiguous_damaged[1..],
-                arrangement_cache,
-            );
-
-            // if the first value was forced to be damaged, then we can't continue searching
-            if spring_information[start_position] == SpringInformation::Damaged {
-                break;
-            }
-
-            start_position += 1;
+        if let Some(&next) = pattern.get(state + 1) {
+            automaton.add_transition(state, next, state + 1);
         }
     }
 
-    arrangement_cache.insert((spring_information, contiguous_damaged), total_arragements);
+    // the final gap is optional
+    automaton.set_accepting(pattern.len() - 1);
+    if pattern.len() > 1 {
+        automaton.set_accepting(pattern.len() - 2);
+    }
 
-    total_arragements
+    automaton
+}
+
+fn working_arrangements(
+    spring_information: &[SpringInformation],
+    contiguous_damaged: &[usize],
+) -> usize {
+    spring_automaton(contiguous_damaged).count_paths(
+        spring_information
+            .iter()
+            .map(|&spring| spring.possible_springs()),
+    )
 }
 
 fn parse(input: &str) -> (Vec<SpringInformation>, Vec<usize>) {
@@ -135,9 +111,7 @@ fn parse(input: &str) -> (Vec<SpringInformation>, Vec<usize>) {
 
 fn arrangements_for_line(input: &str) -> usize {
     let (map, condition_records) = parse(input);
-    let mut cache = HashMap::new();
-
-    working_arrangements(&map, &condition_records, &mut cache)
+    working_arrangements(&map, &condition_records)
 }
 
 fn arrangements_for_longer_line(input: &str) -> usize {
@@ -150,9 +124,7 @@ fn arrangements_for_longer_line(input: &str) -> usize {
         .join(&SpringInformation::MissingInformation);
     let condition_records = condition_records.repeat(5);
 
-    let mut cache = HashMap::new();
-
-    working_arrangements(&map, &condition_records, &mut cache)
+    working_arrangements(&map, &condition_records)
 }
 
 #[test]
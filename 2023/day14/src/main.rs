@@ -1,4 +1,6 @@
-use std::{collections::HashMap, fmt::Debug};
+use std::{env, fmt::Debug};
+
+use aoc_common::{CycleHistory, Recorder};
 
 fn main() {
     let input = include_str!("../input.txt");
@@ -8,6 +10,13 @@ fn main() {
 
     println!("Part 1: {}", ground.load_on_north_beam());
     println!("Part 2: {}", spin_cycle(&spin_ground, 1000000000));
+
+    // pass a path ending in .gif or .png to save an animation of the first few spin cycles
+    if let Some(path) = env::args().nth(1) {
+        let mut recorder = Recorder::new(4, 10);
+        record_spin_cycles(&spin_ground, 20, &mut recorder);
+        recorder.save(path).unwrap();
+    }
 }
 
 #[derive(Clone, Hash, PartialEq, Eq)]
@@ -136,40 +145,44 @@ impl Ground {
             })
             .sum::<usize>()
     }
+
+    fn capture(&self, recorder: &mut Recorder) {
+        recorder.capture(self.ground[0].len(), self.ground.len(), |x, y| {
+            match self.ground[y][x] {
+                Content::Empty => [20, 20, 30],
+                Content::RoundRock => [220, 200, 120],
+                Content::SquareRock => [110, 110, 120],
+            }
+        });
+    }
 }
 
 fn spin_cycle(ground: &Ground, loops: usize) -> usize {
-    let mut cache = HashMap::new();
-
-    let mut ground_to_spin = ground.clone();
-
-    let mut num_loops = 0usize;
+    let history = CycleHistory::find(ground.clone(), |ground| {
+        let mut ground = ground.clone();
+        ground.cycle();
+        ground
+    });
 
-    let loop_length = loop {
-        num_loops += 1;
-
-        ground_to_spin.cycle();
-        let cached = cache.insert(ground_to_spin.clone(), num_loops);
+    history.state_at(loops).load_on_north_beam()
+}
 
-        if let Some(cached) = cached {
-            break num_loops - cached;
+/// Captures a frame after every tilt
+fn record_spin_cycles(ground: &Ground, loops: usize, recorder: &mut Recorder) {
+    let mut ground = ground.clone();
+    ground.capture(recorder);
+
+    for _ in 0..loops {
+        for tilt in [
+            Ground::tilt_north,
+            Ground::tilt_west,
+            Ground::tilt_south,
+            Ground::tilt_east,
+        ] {
+            tilt(&mut ground);
+            ground.capture(recorder);
         }
-    };
-
-    let bit_before_looping = num_loops - loop_length;
-
-    let cache_value_to_use = (loops - bit_before_looping) % loop_length + bit_before_looping;
-
-    cache
-        .iter()
-        .find_map(|(ground, &value)| {
-            if cache_value_to_use == value {
-                Some(ground.load_on_north_beam())
-            } else {
-                None
-            }
-        })
-        .unwrap()
+    }
 }
 
 impl Debug for Ground {
@@ -1,3 +1,5 @@
+use aoc_common::LatticePolygon;
+
 fn main() {
     let input = include_str!("../input.txt");
     println!("Part 1: {}", area(input, InputParseStyle::Part1));
@@ -5,11 +7,11 @@ fn main() {
 }
 
 #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
-struct Point(isize, isize);
+struct Point(i64, i64);
 
 struct Ground {
     lines: Vec<Point>,
-    digger_position: (isize, isize),
+    digger_position: (i64, i64),
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -21,7 +23,7 @@ enum Direction {
 }
 
 impl Direction {
-    fn step_amount(self) -> (isize, isize) {
+    fn step_amount(self) -> (i64, i64) {
         match self {
             Direction::Up => (0, -1),
             Direction::Down => (0, 1),
@@ -51,42 +53,16 @@ impl Ground {
     }
 
     fn total_size(&self) -> usize {
-        // similar to the shoelace theorem, except that everything is axis aligned
-        // so we don't have to worry about differing y values.
-        //
-        // Calculate the area and then use a rearranged pick's theorem to calculate
-        // the required size
-
-        let mut points = self.lines.clone();
-        points.push(self.lines[0]);
-
-        let area = points
-            .windows(2)
-            .map(|window| {
-                let Point(x1, y1) = window[0];
-                let Point(x2, _) = window[1];
-
-                (x1 - x2) * y1
-            })
-            .sum::<isize>()
-            .unsigned_abs();
-
-        let perimeter = points
-            .windows(2)
-            .map(|window| {
-                let Point(x1, y1) = window[0];
-                let Point(x2, y2) = window[1];
-
-                (x1 - x2).unsigned_abs() + (y1 - y2).unsigned_abs()
-            })
-            .sum::<usize>();
-
-        area + perimeter / 2 + 1
+        // the trench covers every lattice point inside the polygon as well as
+        // the ones on its boundary
+        let polygon = LatticePolygon::new(self.lines.iter().map(|&Point(x, y)| (x, y)).collect());
+
+        (polygon.interior_points() + polygon.boundary_points()) as usize
     }
 }
 
 #[derive(Debug, Clone, Copy)]
-struct Instruction(Direction, isize);
+struct Instruction(Direction, i64);
 
 impl Instruction {
     fn parse(line: &str, input_parse_style: InputParseStyle) -> Self {
@@ -109,7 +85,7 @@ impl Instruction {
             InputParseStyle::Part2 => {
                 let instruction = split.skip(2).next().unwrap();
 
-                let distance = isize::from_str_radix(&instruction[2..7], 16).unwrap();
+                let distance = i64::from_str_radix(&instruction[2..7], 16).unwrap();
                 let direction = match instruction.chars().nth(7).unwrap() {
                     '0' => Direction::Right,
                     '1' => Direction::Down,
@@ -1,4 +1,6 @@
-use std::{collections::HashMap, rc::Rc};
+use std::collections::HashMap;
+
+use aoc_common::HyperRect;
 
 fn main() {
     let input = include_str!("../input.txt");
@@ -36,71 +38,8 @@ impl Part {
     }
 }
 
-#[derive(Clone)]
-struct PartRange {
-    x: Rc<[bool; 4000]>,
-    m: Rc<[bool; 4000]>,
-    a: Rc<[bool; 4000]>,
-    s: Rc<[bool; 4000]>,
-}
-
-impl std::fmt::Debug for PartRange {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        macro_rules! debug_value {
-            ($value: ident) => {
-                for &v in self.$value.iter() {
-                    if v {
-                        write!(f, "#")?;
-                    } else {
-                        write!(f, ".")?;
-                    }
-                }
-            };
-        }
-
-        write!(f, "x: ")?;
-        debug_value!(x);
-        write!(f, "\nm: ")?;
-        debug_value!(m);
-        write!(f, "\na: ")?;
-        debug_value!(a);
-        write!(f, "\ns: ")?;
-        debug_value!(s);
-        writeln!(f)?;
-
-        Ok(())
-    }
-}
-
-impl PartRange {
-    fn full() -> Self {
-        Self {
-            x: Rc::new([true; 4000]),
-            m: Rc::new([true; 4000]),
-            a: Rc::new([true; 4000]),
-            s: Rc::new([true; 4000]),
-        }
-    }
-
-    fn empty() -> Self {
-        Self {
-            x: Rc::new([false; 4000]),
-            m: Rc::new([false; 4000]),
-            a: Rc::new([false; 4000]),
-            s: Rc::new([false; 4000]),
-        }
-    }
-
-    fn total_values(&self) -> usize {
-        macro_rules! count_values {
-            ($value: ident) => {
-                self.$value.iter().filter(|&&value| value).count()
-            };
-        }
-
-        count_values!(x) * count_values!(m) * count_values!(a) * count_values!(s)
-    }
-}
+/// Every combination of x, m, a and s in the box
+type PartRange = HyperRect<usize, 4>;
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 enum WorkflowCheck {
@@ -136,21 +75,12 @@ impl WorkflowItemToCheck {
         }
     }
 
-    fn extract_from_part_range(self, part: &PartRange) -> &[bool; 4000] {
-        match self {
-            WorkflowItemToCheck::X => &part.x,
-            WorkflowItemToCheck::M => &part.m,
-            WorkflowItemToCheck::A => &part.a,
-            WorkflowItemToCheck::S => &part.s,
-        }
-    }
-
-    fn set_part_range(self, part: &mut PartRange, values: Rc<[bool; 4000]>) {
+    fn axis(self) -> usize {
         match self {
-            WorkflowItemToCheck::X => part.x = values,
-            WorkflowItemToCheck::M => part.m = values,
-            WorkflowItemToCheck::A => part.a = values,
-            WorkflowItemToCheck::S => part.s = values,
+            WorkflowItemToCheck::X => 0,
+            WorkflowItemToCheck::M => 1,
+            WorkflowItemToCheck::A => 2,
+            WorkflowItemToCheck::S => 3,
         }
     }
 }
@@ -234,7 +164,10 @@ impl WorkflowRule {
     }
 
     // returns ones which pass, ones which don't and where to go
-    fn target_range(&self, part_range: &PartRange) -> (PartRange, PartRange, &'_ str) {
+    fn target_range(
+        &self,
+        part_range: &PartRange,
+    ) -> (Option<PartRange>, Option<PartRange>, &'_ str) {
         match self {
             WorkflowRule::Conditional {
                 item,
@@ -242,45 +175,14 @@ impl WorkflowRule {
                 value,
                 target,
             } => {
-                let mut accepted_part_range = part_range.clone();
-                let mut rejected_part_range = part_range.clone();
-
-                match check {
-                    WorkflowCheck::Greater => {
-                        let mut accepted =
-                            item.extract_from_part_range(&accepted_part_range).clone();
-                        let mut rejected =
-                            item.extract_from_part_range(&rejected_part_range).clone();
-                        for i in 0..*value {
-                            accepted[i] = false;
-                        }
-                        for i in *value..4000 {
-                            rejected[i] = false;
-                        }
-                        item.set_part_range(&mut accepted_part_range, Rc::new(accepted));
-                        item.set_part_range(&mut rejected_part_range, Rc::new(rejected));
-                    }
-                    WorkflowCheck::LessThan => {
-                        let mut accepted =
-                            item.extract_from_part_range(&accepted_part_range).clone();
-                        let mut rejected =
-                            item.extract_from_part_range(&rejected_part_range).clone();
-                        for i in (*value - 1)..4000 {
-                            accepted[i] = false;
-                        }
-                        for i in 0..(*value - 1) {
-                            rejected[i] = false;
-                        }
-                        item.set_part_range(&mut accepted_part_range, Rc::new(accepted));
-                        item.set_part_range(&mut rejected_part_range, Rc::new(rejected));
-                    }
-                }
+                let (accepted, rejected) = match check {
+                    WorkflowCheck::Greater => part_range.split_greater_than(item.axis(), *value),
+                    WorkflowCheck::LessThan => part_range.split_less_than(item.axis(), *value),
+                };
 
-                (accepted_part_range, rejected_part_range, target)
-            }
-            WorkflowRule::Unconditional { target } => {
-                (part_range.clone(), PartRange::empty(), target)
+                (accepted, rejected, target)
             }
+            WorkflowRule::Unconditional { target } => (Some(part_range.clone()), None, target),
         }
     }
 }
@@ -337,8 +239,14 @@ impl Workflow {
         for rule in current_rule {
             let (rule_pass, rule_fail, target) = rule.target_range(&current_parts);
 
-            accepted_parts.append(&mut self.accepted_parts(target, rule_pass));
-            current_parts = rule_fail;
+            if let Some(rule_pass) = rule_pass {
+                accepted_parts.append(&mut self.accepted_parts(target, rule_pass));
+            }
+
+            match rule_fail {
+                Some(rule_fail) => current_parts = rule_fail,
+                None => break,
+            }
         }
 
         accepted_parts
@@ -355,7 +263,7 @@ fn part1(input: &str) -> usize {
     let (rules, parts) = input.split_once("\n\n").unwrap();
     let workflow = Workflow::parse(rules);
 
-    let parts = parts.lines().map(|line| Part::parse(line));
+    let parts = parts.lines().map(Part::parse);
     let accepted_parts = parts.filter(|part| workflow.result(part) == WorkflowResult::Accept);
 
     accepted_parts
@@ -367,10 +275,10 @@ fn part2(input: &str) -> usize {
     let (rules, _parts) = input.split_once("\n\n").unwrap();
     let workflow = Workflow::parse(rules);
 
-    let accepted_parts = workflow.accepted_parts("in", PartRange::full());
+    let accepted_parts = workflow.accepted_parts("in", PartRange::cube(1..4001));
     accepted_parts
         .iter()
-        .map(|accepted_part| accepted_part.total_values())
+        .map(|accepted_part| accepted_part.volume())
         .sum::<usize>()
 }
 
@@ -1,14 +1,26 @@
 use std::{
-    collections::{HashMap, VecDeque},
-    fmt::Debug,
+    collections::VecDeque,
+    env,
+    fmt::{self, Debug, Write},
+    fs,
 };
 
+use aoc_common::{chinese_remainder, BitSet, Cycle, Graph};
+
 fn main() {
     let mut machines = SandMachines::parse(include_str!("../input.txt"));
 
+    // pass a path to write out the module graph for viewing with graphviz
+    if let Some(path) = env::args().nth(1) {
+        fs::write(path, machines.to_dot()).unwrap();
+    }
+
     let part2 = part2(&machines);
     println!("Part 1: {}", part1(&mut machines));
-    println!("Part 2: {part2}");
+    match part2 {
+        Ok(presses) => println!("Part 2: {presses}"),
+        Err(error) => println!("Part 2: can't solve, {error}"),
+    }
 }
 
 #[derive(Clone, Copy, PartialEq, Eq)]
@@ -70,21 +82,81 @@ impl<'a> ModuleParse<'a> {
     }
 }
 
-#[derive(Clone)]
+#[derive(Clone, PartialEq, Eq)]
 enum SandMachineType {
     Broadcaster,
     FlipFlop(Signal),
     Conjunction(Vec<Signal>),
+    /// A module which is only ever sent pulses, like `rx`
+    Output,
 }
 
 #[derive(Clone)]
 struct SandMachines {
+    /// Which modules send pulses to which
+    network: Graph,
     machines: Vec<SandMachineType>,
+    /// The modules which send pulses to each module, so that conjunctions can
+    /// remember the last pulse from each one
+    inputs: Vec<Vec<usize>>,
     broadcast_index: usize,
-    rx_index: Option<usize>,
-    graph: petgraph::graphmap::DiGraphMap<usize, ()>,
+}
+
+/// What happened during a single push of the button
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Press {
+    low_pulses: usize,
+    high_pulses: usize,
+    /// The modules which sent at least one high pulse
+    sent_high: BitSet,
+    /// The modules which received at least one low pulse
+    received_low: BitSet,
+}
+
+/// A group of modules which only interact with the rest of the network through
+/// the broadcaster and a single output feeding the sink's conjunction
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Subnetwork {
+    output: usize,
+    modules: BitSet,
+    /// The first press where `output` sends a high pulse
+    first_high: usize,
+    /// How many presses until it sends a high pulse again
+    period: usize,
+}
+
+/// Why the network doesn't fit the shape needed to find when the sink gets a
+/// low pulse by combining the periods of independent subnetworks
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum StructureError {
+    NoSuchModule(String),
+    NotFedByOneConjunction(String),
+    SubnetworksOverlap(String, String),
+    NoPeriod(String),
+    PeriodsNeverAlign,
+}
 
-    conjunction_loops: HashMap<usize, usize>,
+impl fmt::Display for StructureError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StructureError::NoSuchModule(name) => write!(f, "there is no module {name}"),
+            StructureError::NotFedByOneConjunction(name) => {
+                write!(f, "{name} isn't fed by exactly one conjunction")
+            }
+            StructureError::SubnetworksOverlap(a, b) => {
+                write!(f, "the subnetworks feeding {a} and {b} share modules")
+            }
+            StructureError::NoPeriod(name) => {
+                write!(f, "{name} doesn't send high pulses periodically")
+            }
+            StructureError::PeriodsNeverAlign => {
+                write!(
+                    f,
+                    "the subnetworks never all send high pulses on the same press"
+                )
+            }
+        }
+    }
 }
 
 impl SandMachines {
@@ -94,38 +166,35 @@ impl SandMachines {
     }
 
     fn build(module_parse: &[ModuleParse<'_>]) -> Self {
-        let name_to_index = module_parse
-            .iter()
-            .enumerate()
-            .map(|(index, module)| (module.name, index))
-            .collect::<HashMap<_, _>>();
-
-        let mut graph = petgraph::graphmap::DiGraphMap::new();
+        let mut network = Graph::new_directed();
+        for module in module_parse {
+            network.intern(module.name);
+        }
 
         for (index, module_parsed) in module_parse.iter().enumerate() {
-            let module_id = index;
-            for connection in &module_parsed.connections {
-                graph.add_edge(
-                    module_id,
-                    *name_to_index.get(connection).unwrap_or(&module_parse.len()),
-                    (),
-                );
+            for &connection in &module_parsed.connections {
+                let target = network.intern(connection);
+                network.add_edge(index, target);
             }
         }
 
-        let machines = module_parse
-            .iter()
-            .enumerate()
-            .map(|(index, module)| match module.module_type {
-                ModuleParseType::Broadcaster => SandMachineType::Broadcaster,
-                ModuleParseType::FlipFlop => SandMachineType::FlipFlop(Signal::LowPulse),
-                ModuleParseType::Conjunction => {
-                    let number_of_inputs = graph
-                        .neighbors_directed(index, petgraph::Direction::Incoming)
-                        .count();
-                    SandMachineType::Conjunction(vec![Signal::LowPulse; number_of_inputs])
-                }
-            })
+        let mut inputs = vec![vec![]; network.len()];
+        for (from, to) in network.edges() {
+            inputs[to].push(from);
+        }
+
+        let machines = network
+            .nodes()
+            .map(
+                |index| match module_parse.get(index).map(|module| &module.module_type) {
+                    Some(ModuleParseType::Broadcaster) => SandMachineType::Broadcaster,
+                    Some(ModuleParseType::FlipFlop) => SandMachineType::FlipFlop(Signal::LowPulse),
+                    Some(ModuleParseType::Conjunction) => {
+                        SandMachineType::Conjunction(vec![Signal::LowPulse; inputs[index].len()])
+                    }
+                    None => SandMachineType::Output,
+                },
+            )
             .collect::<Vec<_>>();
 
         let broadcast_index = machines
@@ -133,149 +202,262 @@ impl SandMachines {
             .position(|machine| matches!(machine, SandMachineType::Broadcaster))
             .unwrap();
 
-        let rx_index = Some(module_parse.len());
-
         Self {
-            graph,
+            network,
             machines,
+            inputs,
             broadcast_index,
-            rx_index,
-
-            conjunction_loops: HashMap::new(),
         }
     }
 
-    fn push_button(&mut self, loop_count: usize) -> (usize, usize) {
+    fn index_of(&self, name: &str) -> Option<usize> {
+        self.network.index_of(name)
+    }
+
+    fn push_button(&mut self) -> Press {
+        let mut press = Press {
+            low_pulses: 0,
+            high_pulses: 0,
+            sent_high: BitSet::with_capacity(self.network.len()),
+            received_low: BitSet::with_capacity(self.network.len()),
+        };
+
         let mut pulse_queue = VecDeque::new();
         pulse_queue.push_back((self.broadcast_index, Signal::LowPulse, self.broadcast_index));
 
-        let mut low_pulses_sent = 0;
-        let mut high_pulses_sent = 0;
-
         while let Some((module_index, pulse, sender)) = pulse_queue.pop_front() {
             match pulse {
-                Signal::HighPulse => high_pulses_sent += 1,
-                Signal::LowPulse => low_pulses_sent += 1,
+                Signal::HighPulse => {
+                    press.high_pulses += 1;
+                    press.sent_high.insert(sender);
+                }
+                Signal::LowPulse => {
+                    press.low_pulses += 1;
+                    press.received_low.insert(module_index);
+                }
             }
 
-            match &mut self.machines.get_mut(module_index) {
-                Some(SandMachineType::Broadcaster) => {
-                    for out in self
-                        .graph
-                        .neighbors_directed(module_index, petgraph::Direction::Outgoing)
-                    {
-                        pulse_queue.push_back((out, pulse, module_index));
-                    }
-                }
-                Some(SandMachineType::FlipFlop(ref mut state)) => {
-                    if pulse == Signal::LowPulse {
-                        *state = !*state;
-                        for out in self
-                            .graph
-                            .neighbors_directed(module_index, petgraph::Direction::Outgoing)
-                        {
-                            pulse_queue.push_back((out, *state, module_index));
-                        }
+            let pulse_to_send = match &mut self.machines[module_index] {
+                SandMachineType::Broadcaster => pulse,
+                SandMachineType::FlipFlop(ref mut state) => {
+                    if pulse == Signal::HighPulse {
+                        continue;
                     }
+
+                    *state = !*state;
+                    *state
                 }
-                Some(SandMachineType::Conjunction(ref mut state)) => {
+                SandMachineType::Conjunction(ref mut state) => {
                     // find out who sent this
-                    let sender = self
-                        .graph
-                        .neighbors_directed(module_index, petgraph::Direction::Incoming)
-                        .position(|neighbour| neighbour == sender)
+                    let sender = self.inputs[module_index]
+                        .iter()
+                        .position(|&neighbour| neighbour == sender)
                         .unwrap();
 
                     state[sender] = pulse;
 
-                    let pulse_to_send = if state.iter().all(|&signal| signal == Signal::HighPulse) {
-                        self.conjunction_loops
-                            .entry(module_index)
-                            .or_insert(loop_count);
+                    if state.iter().all(|&signal| signal == Signal::HighPulse) {
                         Signal::LowPulse
                     } else {
                         Signal::HighPulse
-                    };
-
-                    for out in self
-                        .graph
-                        .neighbors_directed(module_index, petgraph::Direction::Outgoing)
-                    {
-                        pulse_queue.push_back((out, pulse_to_send, module_index));
                     }
                 }
-                None => {}
+                SandMachineType::Output => continue,
+            };
+
+            for out in self.network.neighbours(module_index) {
+                pulse_queue.push_back((out, pulse_to_send, module_index));
             }
         }
 
-        (low_pulses_sent, high_pulses_sent)
+        press
     }
 
-    fn presses_until_rx(&self) -> usize {
-        let mut test_instance = self.clone();
+    /// Every module which can send pulses to `module`, directly or indirectly,
+    /// other than the broadcaster
+    fn feeding(&self, module: usize) -> BitSet {
+        let mut seen = BitSet::with_capacity(self.network.len());
+        let mut stack = vec![module];
 
-        let input_to_rx = self
-            .graph
-            .neighbors_directed(self.rx_index.unwrap(), petgraph::Direction::Incoming)
-            .next()
-            .unwrap();
+        while let Some(current) = stack.pop() {
+            if current == self.broadcast_index || !seen.insert(current) {
+                continue;
+            }
 
-        let input_to_input = self
-            .graph
-            .neighbors_directed(input_to_rx, petgraph::Direction::Incoming)
-            .flat_map(|neighbour| {
-                self.graph
-                    .neighbors_directed(neighbour, petgraph::Direction::Incoming)
-            })
-            .collect::<Vec<_>>();
+            stack.extend(&self.inputs[current]);
+        }
 
-        let mut input_times = HashMap::with_capacity(input_to_input.len());
+        seen
+    }
 
-        for button_push in 1.. {
-            test_instance.push_button(button_push);
+    /// Splits the network feeding `sink` into independent subnetworks, each
+    /// of which feeds the single conjunction in front of the sink, and works
+    /// out how often each one sends it a high pulse
+    fn subnetworks(&self, sink: &str) -> Result<Vec<Subnetwork>, StructureError> {
+        let sink_index = self
+            .index_of(sink)
+            .ok_or_else(|| StructureError::NoSuchModule(sink.to_string()))?;
+
+        let hub = match self.inputs[sink_index][..] {
+            [hub] if matches!(self.machines[hub], SandMachineType::Conjunction(_)) => hub,
+            _ => return Err(StructureError::NotFedByOneConjunction(sink.to_string())),
+        };
 
-            for &input in &input_to_input {
-                if let Some(loop_) = test_instance.conjunction_loops.get(&input) {
-                    input_times.entry(input).or_insert(*loop_);
-                }
+        let mut subnetworks: Vec<Subnetwork> = vec![];
+        for &output in &self.inputs[hub] {
+            let modules = self.feeding(output);
+            if modules.contains(hub) {
+                return Err(StructureError::NotFedByOneConjunction(sink.to_string()));
             }
 
-            if input_times.len() == input_to_input.len() {
-                break;
+            if let Some(other) = subnetworks
+                .iter()
+                .find(|other| other.modules.intersection_len(&modules) > 0)
+            {
+                return Err(StructureError::SubnetworksOverlap(
+                    self.network.name(other.output).to_string(),
+                    self.network.name(output).to_string(),
+                ));
             }
+
+            subnetworks.push(Subnetwork {
+                output,
+                modules,
+                first_high: 0,
+                period: 0,
+            });
+        }
+
+        for subnetwork in &mut subnetworks {
+            let (first_high, period) = self.subnetwork_period(subnetwork).ok_or_else(|| {
+                StructureError::NoPeriod(self.network.name(subnetwork.output).to_string())
+            })?;
+            subnetwork.first_high = first_high;
+            subnetwork.period = period;
         }
 
-        input_times.values().copied().fold(1, lcm)
+        Ok(subnetworks)
     }
-}
 
-fn lcm(a: usize, b: usize) -> usize {
-    a * b / gcd(a, b)
-}
+    /// Finds the cycle in the states of a subnetwork's own modules, and checks
+    /// that its output sends a high pulse on exactly one press of that cycle and
+    /// never before it. Returns the first such press and the cycle length.
+    ///
+    /// Never returns if the subnetwork's state never repeats within memory, though
+    /// the networks in the puzzle inputs cycle within a few thousand presses
+    fn subnetwork_period(&self, subnetwork: &Subnetwork) -> Option<(usize, usize)> {
+        // only the subnetwork's modules get pulses from the broadcaster, and it
+        // can't be affected by anything outside itself
+        let mut isolated = self.clone();
+        for out in self.network.neighbours(self.broadcast_index) {
+            if !subnetwork.modules.contains(out) {
+                isolated.network.remove_edge(self.broadcast_index, out);
+            }
+        }
+
+        let save = |machines: &SandMachines| -> Vec<SandMachineType> {
+            subnetwork
+                .modules
+                .iter()
+                .map(|module| machines.machines[module].clone())
+                .collect()
+        };
+        let load = |machines: &mut SandMachines, state: &[SandMachineType]| {
+            for (module, machine) in subnetwork.modules.iter().zip(state) {
+                machines.machines[module] = machine.clone();
+            }
+        };
+
+        let start = save(&isolated);
+        let cycle = Cycle::brent(&start, |state| {
+            load(&mut isolated, state);
+            isolated.push_button();
+            save(&isolated)
+        });
+
+        load(&mut isolated, &start);
+        let high_presses: Vec<_> = (1..=cycle.prefix_length + cycle.cycle_length)
+            .filter(|_| isolated.push_button().sent_high.contains(subnetwork.output))
+            .collect();
+
+        match high_presses[..] {
+            [first_high] if first_high > cycle.prefix_length => {
+                Some((first_high, cycle.cycle_length))
+            }
+            _ => None,
+        }
+    }
+
+    fn presses_until_low_pulse(&self, sink: &str) -> Result<usize, StructureError> {
+        let subnetworks = self.subnetworks(sink)?;
+
+        let (first_aligned, period) = chinese_remainder(
+            subnetworks
+                .iter()
+                .map(|subnetwork| (subnetwork.first_high as i64, subnetwork.period as i64)),
+        )
+        .ok_or(StructureError::PeriodsNeverAlign)?;
+
+        // every subnetwork has to have started before they can line up
+        let earliest = subnetworks
+            .iter()
+            .map(|subnetwork| subnetwork.first_high)
+            .max()
+            .unwrap_or(1) as i64;
+        let behind = (earliest - first_aligned).max(0);
+        let presses = first_aligned + (behind + period - 1) / period * period;
 
-fn gcd(a: usize, b: usize) -> usize {
-    if b == 0 {
-        a
-    } else {
-        gcd(b, a % b)
+        Ok(presses as usize)
+    }
+
+    fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph modules {\n");
+
+        for index in self.network.nodes() {
+            let name = self.network.name(index);
+            let (label, shape) = match self.machines[index] {
+                SandMachineType::Broadcaster => (name.to_string(), "doubleoctagon"),
+                SandMachineType::FlipFlop(_) => (format!("%{name}"), "box"),
+                SandMachineType::Conjunction(_) => (format!("&{name}"), "diamond"),
+                SandMachineType::Output => (name.to_string(), "doublecircle"),
+            };
+
+            writeln!(dot, "    \"{name}\" [label=\"{label}\", shape={shape}];").unwrap();
+        }
+
+        for (from, to) in self.network.edges() {
+            writeln!(
+                dot,
+                "    \"{}\" -> \"{}\";",
+                self.network.name(from),
+                self.network.name(to)
+            )
+            .unwrap();
+        }
+
+        dot.push_str("}\n");
+        dot
     }
 }
 
+/// How many times part 1 pushes the button
+const PART1_PRESSES: usize = 1000;
+
 fn part1(machines: &mut SandMachines) -> usize {
     let mut total_high = 0;
     let mut total_low = 0;
 
-    for i in 1..1000 {
-        let (lows, highs) = machines.push_button(i);
-        total_high += highs;
-        total_low += lows;
+    for _ in 0..PART1_PRESSES {
+        let press = machines.push_button();
+        total_high += press.high_pulses;
+        total_low += press.low_pulses;
     }
 
     total_high * total_low
 }
 
-fn part2(machines: &SandMachines) -> usize {
-    machines.presses_until_rx()
+fn part2(machines: &SandMachines) -> Result<usize, StructureError> {
+    machines.presses_until_low_pulse("rx")
 }
 
 #[test]
@@ -288,7 +470,8 @@ fn given_input1() {
 &inv -> a",
     );
 
-    assert_eq!(machine.push_button(1), (8, 4));
+    let press = machine.push_button();
+    assert_eq!((press.low_pulses, press.high_pulses), (8, 4));
 }
 
 #[test]
@@ -303,3 +486,79 @@ fn given_input2() {
 
     assert_eq!(part1(&mut machine), 11687500);
 }
+
+#[cfg(test)]
+const COUNTERS: &str = "broadcaster -> f1, g1
+%f1 -> n1
+&n1 -> hub
+%g1 -> g2
+%g2 -> m2
+&m2 -> hub
+&hub -> rx";
+
+#[test]
+fn subnetwork_periods() {
+    let machines = SandMachines::parse(COUNTERS);
+
+    let subnetworks = machines.subnetworks("rx").unwrap();
+    let periods: Vec<_> = subnetworks
+        .iter()
+        .map(|subnetwork| (subnetwork.first_high, subnetwork.period))
+        .collect();
+    assert_eq!(periods, vec![(2, 2), (4, 4)]);
+    assert_eq!(subnetworks[1].modules.len(), 3);
+
+    assert_eq!(part2(&machines), Ok(4));
+
+    // check against brute force
+    let rx = machines.index_of("rx").unwrap();
+    let mut simulation = machines.clone();
+    let presses = (1..)
+        .find(|_| simulation.push_button().received_low.contains(rx))
+        .unwrap();
+    assert_eq!(presses, 4);
+}
+
+#[test]
+fn reports_unsupported_structure() {
+    let machines = SandMachines::parse(COUNTERS);
+    assert_eq!(
+        machines.presses_until_low_pulse("n1"),
+        Err(StructureError::NotFedByOneConjunction("n1".to_string()))
+    );
+    assert_eq!(
+        machines.presses_until_low_pulse("nowhere"),
+        Err(StructureError::NoSuchModule("nowhere".to_string()))
+    );
+
+    let shared = SandMachines::parse(
+        "broadcaster -> a
+%a -> n1, n2
+&n1 -> hub
+&n2 -> hub
+&hub -> rx",
+    );
+    assert_eq!(
+        part2(&shared),
+        Err(StructureError::SubnetworksOverlap(
+            "n1".to_string(),
+            "n2".to_string()
+        ))
+    );
+}
+
+#[test]
+fn dot_export() {
+    let machines = SandMachines::parse("broadcaster -> a\n%a -> rx");
+    assert_eq!(
+        machines.to_dot(),
+        "digraph modules {
+    \"broadcaster\" [label=\"broadcaster\", shape=doubleoctagon];
+    \"a\" [label=\"%a\", shape=box];
+    \"rx\" [label=\"rx\", shape=doublecircle];
+    \"broadcaster\" -> \"a\";
+    \"a\" -> \"rx\";
+}
+"
+    );
+}
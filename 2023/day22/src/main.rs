@@ -1,3 +1,4 @@
+use aoc_common::Graph;
 use itertools::Itertools;
 use std::collections::HashMap;
 
@@ -110,32 +111,92 @@ impl Sky {
         number_that_fell
     }
 
-    fn disintegratable_blocks(&mut self) -> usize {
-        let mut count = 0;
-        self.bricks.sort_by_key(|brick| brick.height_from_ground());
+    /// Which bricks rest directly on which, as a graph with an edge from each
+    /// brick to every brick sitting on top of it. Node `i` is `bricks[i]`.
+    fn supports(&self) -> Graph {
+        let mut graph = Graph::new_directed();
+        for _ in &self.bricks {
+            graph.add_node();
+        }
 
-        for (i, _) in self.bricks.iter().enumerate() {
-            if self.number_of_blocks_that_would_fall(i) == 0 {
-                count += 1;
+        let mut cells = HashMap::new();
+        for (i, brick) in self.bricks.iter().enumerate() {
+            for (x, y) in brick.project_down() {
+                for z in brick.height_from_ground()..=brick.height_of_top() {
+                    cells.insert([x, y, z], i);
+                }
             }
         }
 
-        count
+        for (i, brick) in self.bricks.iter().enumerate() {
+            let Some(below) = brick.height_from_ground().checked_sub(1) else {
+                continue;
+            };
+
+            for (x, y) in brick.project_down() {
+                if let Some(&supporter) = cells.get(&[x, y, below]) {
+                    graph.add_edge(supporter, i);
+                }
+            }
+        }
+
+        graph
     }
 
-    fn chain_reaction(&mut self) -> usize {
-        self.bricks.sort_by_key(|brick| brick.height_from_ground());
+    /// The bricks directly underneath each brick
+    fn supporters(supports: &Graph) -> Vec<Vec<usize>> {
+        let mut supporters = vec![vec![]; supports.len()];
+        for (below, above) in supports.edges() {
+            supporters[above].push(below);
+        }
 
-        (0..self.bricks.len())
-            .map(|i| self.number_of_blocks_that_would_fall(i))
-            .sum::<usize>()
+        supporters
     }
 
-    fn number_of_blocks_that_would_fall(&self, brick_index: usize) -> usize {
-        let mut dupe_sky = self.clone();
-        dupe_sky.bricks.swap_remove(brick_index);
+    fn disintegratable_blocks(&self) -> usize {
+        let supports = self.supports();
+        let supporters = Self::supporters(&supports);
+
+        supports
+            .nodes()
+            .filter(|&brick| {
+                supports
+                    .neighbours(brick)
+                    .all(|above| supporters[above].len() > 1)
+            })
+            .count()
+    }
+
+    fn chain_reaction(&self) -> usize {
+        let supports = self.supports();
+        let supporters = Self::supporters(&supports);
+
+        // every brick comes after the bricks holding it up, so a single pass
+        // in this order sees whether all of a brick's supporters have fallen
+        let order = supports
+            .topological_sort()
+            .expect("Bricks can't hold each other up in a loop");
+
+        order
+            .iter()
+            .enumerate()
+            .map(|(position, &removed)| {
+                let mut fallen = vec![false; self.bricks.len()];
+                fallen[removed] = true;
+
+                let mut count = 0;
+                for &brick in &order[position + 1..] {
+                    if !supporters[brick].is_empty()
+                        && supporters[brick].iter().all(|&below| fallen[below])
+                    {
+                        fallen[brick] = true;
+                        count += 1;
+                    }
+                }
 
-        dupe_sky.drop_everything()
+                count
+            })
+            .sum()
     }
 }
 
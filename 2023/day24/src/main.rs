@@ -1,177 +1,47 @@
+use aoc_common::{Intersection, Line3, Rational};
 use itertools::Itertools;
-use std::{
-    collections::HashSet,
-    ops::{Add, Mul, Sub},
-};
+use std::collections::HashSet;
 
 fn main() {
     let input = include_str!("../input.txt");
-    println!(
-        "Part1: {}",
-        part1(input, 200000000000000., 400000000000000.)
-    );
+    println!("Part1: {}", part1(input, 200000000000000, 400000000000000));
 
     println!("Part 2: {}", part2(input));
 }
 
-#[derive(Clone)]
-struct Line {
-    start_point: Point3d,
-    velocity: Point3d,
-}
-
-#[derive(Clone, Copy, PartialEq)]
-struct Point3d {
-    x: f64,
-    y: f64,
-    z: f64,
-}
-
-impl Point3d {
-    fn parse(input: &str) -> Self {
-        let pos = input
-            .split(", ")
-            .map(|text| text.trim().parse::<f64>().unwrap())
-            .collect::<Vec<_>>();
-
-        Self {
-            x: pos[0],
-            y: pos[1],
-            z: pos[2],
-        }
-    }
-
-    fn project(self) -> Self {
-        Self {
-            x: self.x,
-            y: self.y,
-            z: 0.,
-        }
-    }
-
-    fn distance_sq(self, other: &Self) -> f64 {
-        (self.x - other.x).powi(2) + (self.y - other.y).powi(2) + (self.z - other.z).powi(2)
-    }
-}
-
-impl std::fmt::Debug for Point3d {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "({}, {}, {})", self.x, self.y, self.z)
-    }
-}
-
-impl Add<Point3d> for Point3d {
-    type Output = Point3d;
-
-    fn add(self, rhs: Point3d) -> Self::Output {
-        Self {
-            x: self.x + rhs.x,
-            y: self.y + rhs.y,
-            z: self.z + rhs.z,
-        }
-    }
-}
-
-impl Sub<Point3d> for Point3d {
-    type Output = Point3d;
+fn parse_vector(input: &str) -> [i128; 3] {
+    let pos = input
+        .split(", ")
+        .map(|text| text.trim().parse::<i128>().unwrap())
+        .collect::<Vec<_>>();
 
-    fn sub(self, rhs: Point3d) -> Self::Output {
-        Self {
-            x: self.x - rhs.x,
-            y: self.y - rhs.y,
-            z: self.z - rhs.z,
-        }
-    }
+    [pos[0], pos[1], pos[2]]
 }
 
-impl Mul<f64> for Point3d {
-    type Output = Point3d;
+fn parse_line(input: &str) -> Line3 {
+    let (start_pos, velocity) = input.split_once(" @ ").unwrap();
 
-    fn mul(self, rhs: f64) -> Self::Output {
-        Self {
-            x: self.x * rhs,
-            y: self.y * rhs,
-            z: self.z * rhs,
-        }
-    }
+    Line3::new(parse_vector(start_pos), parse_vector(velocity))
 }
 
-impl Line {
-    fn parse(input: &str) -> Self {
-        let (start_pos, velocity) = input.split_once(" @ ").unwrap();
-
-        Self {
-            start_point: Point3d::parse(start_pos),
-            velocity: Point3d::parse(velocity),
-        }
-    }
-
-    fn at(&self, t: f64) -> Point3d {
-        self.start_point + self.velocity * t
-    }
-
-    fn intersect_2d(&self, other: &Line) -> Option<(f64, f64)> {
-        let a1 = self.velocity.x;
-        let b1 = self.velocity.y;
-        let c1 = self.start_point.x;
-        let d1 = self.start_point.y;
-
-        let a2 = other.velocity.x;
-        let b2 = other.velocity.y;
-        let c2 = other.start_point.x;
-        let d2 = other.start_point.y;
-
-        let denominator = b2 * a1 - a2 * b1;
-        if denominator.abs() < 0.00001 {
-            return None;
-        }
-
-        let t1 = (b2 * (c2 - c1) - a2 * (d2 - d1)) as f64 / denominator as f64;
-        let t2 = (b1 * (c1 - c2) - a1 * (d1 - d2)) as f64 / -denominator as f64;
-
-        Some((t1, t2))
-    }
-
-    fn intersect_3d(&self, other: &Line) -> Option<(f64, f64)> {
-        let (t1, t2) = self.intersect_2d(other)?;
-
-        if self.at(t1).distance_sq(&other.at(t2)) < 0.00001 {
-            return None;
-        }
-
-        Some((t1, t2))
-    }
-
-    fn project(&self) -> Self {
-        Self {
-            start_point: self.start_point.project(),
-            velocity: self.velocity.project(),
-        }
-    }
-}
-
-fn part1(input: &str, start: f64, end: f64) -> usize {
+fn part1(input: &str, start: i128, end: i128) -> usize {
+    // hailstones which don't move in x or y are deliberately ignored, even
+    // though another hailstone's path could pass through where they sit
     let lines = input
         .lines()
-        .map(|line| Line::parse(line))
+        .filter_map(|line| parse_line(line).project([0, 1]))
         .collect::<Vec<_>>();
 
+    let area = Rational::integer(start)..=Rational::integer(end);
+
     let mut collisions = 0;
 
     for (i, l1) in lines.iter().enumerate() {
         for l2 in lines.iter().skip(i + 1) {
-            if let Some((t1, t2)) = l1.intersect_2d(l2) {
-                if t1 < 0. || t2 < 0. {
-                    continue;
-                }
-
-                let intersection_point = l1.at(t1);
+            if let Some((t1, _)) = l1.ray_intersection(l2) {
+                let [x, y] = l1.at(t1);
 
-                if start <= intersection_point.x
-                    && intersection_point.x <= end
-                    && start <= intersection_point.y
-                    && intersection_point.y <= end
-                {
+                if area.contains(&x) && area.contains(&y) {
                     collisions += 1;
                 }
             }
@@ -181,61 +51,65 @@ fn part1(input: &str, start: f64, end: f64) -> usize {
     collisions
 }
 
-fn part2(input: &str) -> i64 {
-    let mut lines = input
-        .lines()
-        .map(|line| Line::parse(line))
-        .collect::<Vec<_>>();
+fn part2(input: &str) -> i128 {
+    let mut lines = input.lines().map(parse_line).collect::<Vec<_>>();
 
-    let x_velocity = get_velocity(&mut lines, |point| point.x);
-    let y_velocity = get_velocity(&mut lines, |point| point.y);
-    let z_velocity = get_velocity(&mut lines, |point| point.z);
+    let x_velocity = get_velocity(&mut lines, 0);
+    let y_velocity = get_velocity(&mut lines, 1);
+    let z_velocity = get_velocity(&mut lines, 2);
 
-    let rock_velocity = Point3d {
-        x: x_velocity as f64,
-        y: y_velocity as f64,
-        z: z_velocity as f64,
-    };
+    let rock_velocity = [x_velocity, y_velocity, z_velocity];
 
-    println!("{x_velocity} {y_velocity} {z_velocity}");
+    rock_position(&lines, rock_velocity).iter().sum()
+}
 
-    let line1 = Line {
-        start_point: lines[0].start_point,
-        velocity: lines[0].velocity - rock_velocity,
-    };
-    let line2 = Line {
-        start_point: lines[1].start_point,
-        velocity: lines[1].velocity - rock_velocity,
+fn rock_position(lines: &[Line3], rock_velocity: [i128; 3]) -> [i128; 3] {
+    // in the rock's frame of reference, every hailstone passes through the
+    // rock's starting position. A hailstone moving at the rock's velocity
+    // doesn't move at all in that frame, so it can't be used.
+    let mut relative_to_rock = lines
+        .iter()
+        .filter(|line| line.direction != rock_velocity)
+        .map(|line| {
+            Line3::new(
+                line.origin,
+                [0, 1, 2].map(|i| line.direction[i] - rock_velocity[i]),
+            )
+        });
+    let line1 = relative_to_rock
+        .next()
+        .expect("not enough moving hailstones");
+    let line2 = relative_to_rock
+        .next()
+        .expect("not enough moving hailstones");
+
+    let Intersection::Point { t: t1, .. } = line1.intersect(&line2) else {
+        panic!("does not intersect...");
     };
-
-    let (t1, _) = line1.intersect_3d(&line2).expect("does not intersect...");
-    let rock = line1.at(t1);
-
-    println!("rock: {rock:?} with velocity: {rock_velocity:?}");
-    rock.x as i64 + rock.y as i64 + rock.z as i64
+    line1.at(t1).map(|value| {
+        value
+            .to_integer()
+            .expect("rock starts at a non-integer position")
+    })
 }
 
-fn get_velocity(lines: &mut Vec<Line>, extract: impl Fn(Point3d) -> f64) -> i64 {
+fn get_velocity(lines: &mut [Line3], axis: usize) -> i128 {
     let mut possible_velocities = HashSet::new();
-    // find all the ones with the same x velocity
-    lines.sort_by_key(|line| extract(line.velocity) as i64);
-    let identical_velocity = lines.iter().group_by(|line| extract(line.velocity));
+    // find all the ones with the same velocity along this axis
+    lines.sort_by_key(|line| line.direction[axis]);
+    let identical_velocity = lines.iter().group_by(|line| line.direction[axis]);
     for (velocity, group) in &identical_velocity {
         let items = group.collect::<Vec<_>>();
         if items.len() == 1 {
             continue;
         }
 
-        let required_for_factors =
-            (extract(items[0].start_point) - extract(items[1].start_point)).abs();
+        let required_for_factors = items[0].origin[axis].abs_diff(items[1].origin[axis]);
         let factors_for_velocity = factors(required_for_factors as u64);
 
-        let possible_values_here = factors_for_velocity.iter().flat_map(|&factor| {
-            [
-                velocity as i64 + factor as i64,
-                velocity as i64 - factor as i64,
-            ]
-        });
+        let possible_values_here = factors_for_velocity
+            .iter()
+            .flat_map(|&factor| [velocity + factor as i128, velocity - factor as i128]);
 
         if possible_velocities.is_empty() {
             for possible_value in possible_values_here {
@@ -266,7 +140,7 @@ fn factors(input: u64) -> Vec<u64> {
     let mut result = vec![1, input];
 
     while test * test <= input {
-        if input % test == 0 {
+        if input.is_multiple_of(test) {
             result.push(test);
         }
         test += 1;
@@ -279,39 +153,43 @@ fn factors(input: u64) -> Vec<u64> {
 fn intersection() {
     // 19, 13, 30 @ -2,  1, -2
     // 18, 19, 22 @ -1, -1, -2
-    // 20, 25, 34 @ -2, -2, -4
-    // 12, 31, 28 @ -1, -2, -1
-    // 20, 19, 15 @  1, -5, -3
-
-    let l1 = Line {
-        start_point: Point3d {
-            x: 19.,
-            y: 13.,
-            z: 30.,
-        },
-        velocity: Point3d {
-            x: -2.,
-            y: 1.,
-            z: -2.,
-        },
-    };
+    let l1 = parse_line("19, 13, 30 @ -2,  1, -2")
+        .project([0, 1])
+        .unwrap();
+    let l2 = parse_line("18, 19, 22 @ -1, -1, -2")
+        .project([0, 1])
+        .unwrap();
 
-    let l2 = Line {
-        start_point: Point3d {
-            x: 18.,
-            y: 19.,
-            z: 22.,
-        },
-        velocity: Point3d {
-            x: -1.,
-            y: -1.,
-            z: -2.,
-        },
-    };
+    let (t1, t2) = l1.ray_intersection(&l2).unwrap();
 
-    let (t1, t2) = l1.intersect_2d(&l2).unwrap();
+    assert_eq!(l1.at(t1), l2.at(t2));
+    assert_eq!(l1.at(t1), [Rational::new(43, 3), Rational::new(46, 3)]);
+}
+
+#[test]
+fn stationary_hailstones_are_ignored() {
+    assert_eq!(
+        part1(
+            "19, 13, 30 @ -2,  1, -2
+18, 19, 22 @ -1, -1, -2
+15, 14, 20 @  0,  0,  3",
+            7,
+            27,
+        ),
+        1
+    );
+}
+
+#[test]
+fn hailstone_moving_with_the_rock() {
+    let lines = "24, 13, 10 @ -3,  1,  2
+19, 13, 30 @ -2,  1, -2
+18, 19, 22 @ -1, -1, -2"
+        .lines()
+        .map(parse_line)
+        .collect::<Vec<_>>();
 
-    assert!(l1.at(t1).project().distance_sq(&l2.at(t2).project()) < 0.00001);
+    assert_eq!(rock_position(&lines, [-3, 1, 2]), [24, 13, 10]);
 }
 
 #[test]
@@ -323,8 +201,8 @@ fn part1_given_input() {
 20, 25, 34 @ -2, -2, -4
 12, 31, 28 @ -1, -2, -1
 20, 19, 15 @  1, -5, -3",
-            7.,
-            27.,
+            7,
+            27,
         ),
         2
     );
@@ -1,5 +1,7 @@
 use std::{fs, ops::Range};
 
+use aoc_common::RangeSet;
+
 fn main() {
     let input = fs::read_to_string("input.txt").unwrap();
     let almanac1 = Almanac::parse(&input, SeedParsingStrategy::Values);
@@ -11,7 +13,7 @@ fn main() {
 }
 
 struct Almanac {
-    seeds: Vec<Range<usize>>,
+    seeds: RangeSet<usize>,
     maps: Vec<Map>,
 }
 
@@ -63,23 +65,13 @@ impl Almanac {
     }
 
     fn closest_seed(&self) -> usize {
-        self.seeds
-            .iter()
-            .flat_map(|seed_range| self.seed_range(seed_range))
-            .map(|range| range.start)
-            .min()
-            .unwrap()
-    }
-
-    fn seed_range(&self, seed_range: &Range<usize>) -> Vec<Range<usize>> {
         self.maps
             .iter()
-            .fold(vec![seed_range.clone()], |seed_ranges, map| {
-                seed_ranges
-                    .iter()
-                    .flat_map(|seed_range| map.get_ranges(seed_range))
-                    .collect()
+            .fold(self.seeds.clone(), |ranges, map| {
+                ranges.map_piecewise(&map.offsets)
             })
+            .min()
+            .unwrap()
     }
 }
 
@@ -119,43 +111,6 @@ impl Map {
 
         item
     }
-
-    fn get_ranges(&self, range: &Range<usize>) -> Vec<Range<usize>> {
-        let mut offsets = self.offsets.clone();
-        offsets.sort_by_key(|o| o.0.start);
-
-        let mut result = vec![];
-        let mut i = range.start;
-
-        while i < range.end {
-            if let Some((r, dest_start)) = offsets.iter().find(|o| o.0.contains(&i)) {
-                let intersection = r.start.max(range.start)..r.end.min(range.end);
-                let start = dest_start + i - r.start;
-
-                result.push(start..(start + intersection.end - intersection.start));
-                i = intersection.end;
-            } else if let Some(n) = offsets.iter().rposition(|(o, _)| o.contains(&i)) {
-                // this is the last one which contains i, so we actually want the next one.
-                if let Some((next, _)) = offsets.get(n + 1) {
-                    result.push(i..next.start);
-                    i = next.start;
-                } else {
-                    result.push(i..range.end);
-                    i = range.end;
-                }
-            } else {
-                result.push(i..range.end);
-                i = range.end;
-            }
-        }
-
-        assert_eq!(
-            result.iter().map(|r| r.end - r.start).sum::<usize>(),
-            range.end - range.start
-        );
-
-        result
-    }
 }
 
 #[test]
@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use aoc_common::{lcm_all, Graph};
 
 fn main() {
     let input = include_str!("../input.txt");
@@ -6,47 +6,36 @@ fn main() {
     println!("Part 2: {}", follow_all_map(input));
 }
 
-#[derive(Debug, Clone, Hash, PartialEq, Eq)]
-struct Node(String);
-
 struct Map {
-    nodes: Vec<Node>,
+    nodes: Graph,
 
+    /// The nodes reached by going left and right from each node. The graph
+    /// can't tell its edges apart, so they're kept here as well.
     map: Vec<(usize, usize)>,
 }
 
 impl Map {
     fn parse(input: &str) -> Self {
-        let mut nodes = HashMap::new();
-        let mut node_to_id = HashMap::new();
-
-        let mut nodes_vec = vec![];
+        let mut nodes = Graph::new_directed();
+        let mut map = vec![];
 
         for line in input.lines() {
-            let start = line[0..3].to_owned();
-            let left = line[7..10].to_owned();
-            let right = line[12..15].to_owned();
-
-            let start = Node(start);
-            nodes.insert(start.clone(), (Node(left), Node(right)));
-            node_to_id.insert(start.clone(), nodes_vec.len());
-            nodes_vec.push(start);
-        }
+            let start = nodes.intern(&line[0..3]);
+            let left = nodes.intern(&line[7..10]);
+            let right = nodes.intern(&line[12..15]);
 
-        let mut map = vec![Default::default(); nodes.len()];
+            nodes.add_edge(start, left);
+            nodes.add_edge(start, right);
 
-        for (start, (left, right)) in nodes.iter() {
-            map[node_to_id[start]] = (node_to_id[left], node_to_id[right]);
+            map.resize(nodes.len(), Default::default());
+            map[start] = (left, right);
         }
 
-        Map {
-            nodes: nodes_vec,
-            map,
-        }
+        Map { nodes, map }
     }
 
     fn follow(&self, input: &str) -> usize {
-        let mut current = self.nodes.iter().position(|x| x.0 == "AAA").unwrap();
+        let mut current = self.nodes.index_of("AAA").unwrap();
         let mut count = 0;
 
         for c in input.chars().cycle() {
@@ -58,7 +47,7 @@ impl Map {
                 current = right;
             }
 
-            if self.nodes[current].0 == "ZZZ" {
+            if self.nodes.name(current) == "ZZZ" {
                 return count;
             }
         }
@@ -69,15 +58,8 @@ impl Map {
     fn follow_all(&self, input: &str) -> usize {
         let starting_positions: Vec<_> = self
             .nodes
-            .iter()
-            .enumerate()
-            .filter_map(|(id, start)| {
-                if start.0.ends_with('A') {
-                    Some(id)
-                } else {
-                    None
-                }
-            })
+            .nodes()
+            .filter(|&id| self.nodes.name(id).ends_with('A'))
             .collect();
 
         let mut cycle_lengths = vec![];
@@ -107,9 +89,7 @@ impl Map {
             }
         }
 
-        cycle_lengths
-            .iter()
-            .fold(1, |curr, cycle| num::integer::lcm(curr, *cycle))
+        lcm_all(cycle_lengths.iter().map(|&cycle| cycle as u64)) as usize
     }
 }
 
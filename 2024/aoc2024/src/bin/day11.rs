@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use aoc2024::{Checked, CheckedSumExt, Memo};
 
 fn main() {
     let input = aoc2024::get_input(11);
@@ -10,52 +10,46 @@ fn main() {
 fn blinks(input: &str, blinks: usize) -> usize {
     let stones = input.split(' ').map(|i| i.parse::<usize>().unwrap());
 
-    let mut cache = HashMap::new();
+    let mut memo = Memo::new();
     stones
-        .map(|stone| number_after_iterations(stone, blinks, &mut cache))
-        .sum()
+        .map(|stone| number_after_iterations(stone, blinks, &mut memo))
+        .checked_sum()
 }
 
 fn number_after_iterations(
     stone: usize,
     iterations_remaining: usize,
-    cache: &mut HashMap<(usize, usize), usize>,
+    memo: &mut Memo<(usize, usize), usize>,
 ) -> usize {
     if iterations_remaining == 0 {
         return 1;
     }
 
-    if let Some(cached) = cache.get(&(stone, iterations_remaining)) {
-        return *cached;
-    }
-
-    if stone == 0 {
-        let result = number_after_iterations(1, iterations_remaining - 1, cache);
-        cache.insert((stone, iterations_remaining), result);
-        return result;
-    }
-
-    let as_str = stone.to_string();
-    if as_str.len() % 2 == 0 {
-        let (first_half, second_half) = as_str.split_at(as_str.len() / 2);
-
-        let first =
-            number_after_iterations(first_half.parse().unwrap(), iterations_remaining - 1, cache);
-        let second = number_after_iterations(
-            second_half.parse().unwrap(),
-            iterations_remaining - 1,
-            cache,
-        );
-
-        let result = first + second;
-        cache.insert((stone, iterations_remaining), result);
-        return result;
-    }
-
-    let result = number_after_iterations(stone * 2024, iterations_remaining - 1, cache);
-    cache.insert((stone, iterations_remaining), result);
-
-    result
+    memo.get_or_insert_with((stone, iterations_remaining), |memo| {
+        if stone == 0 {
+            return number_after_iterations(1, iterations_remaining - 1, memo);
+        }
+
+        let as_str = stone.to_string();
+        if as_str.len().is_multiple_of(2) {
+            let (first_half, second_half) = as_str.split_at(as_str.len() / 2);
+
+            let first = number_after_iterations(
+                first_half.parse().unwrap(),
+                iterations_remaining - 1,
+                memo,
+            );
+            let second = number_after_iterations(
+                second_half.parse().unwrap(),
+                iterations_remaining - 1,
+                memo,
+            );
+
+            return (Checked(first) + second).0;
+        }
+
+        number_after_iterations((Checked(stone) * 2024).0, iterations_remaining - 1, memo)
+    })
 }
 
 #[test]
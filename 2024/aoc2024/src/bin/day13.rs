@@ -1,4 +1,4 @@
-use aoc2024::get_input;
+use aoc2024::{get_input, Matrix};
 
 fn main() {
     let input = get_input(13);
@@ -82,27 +82,13 @@ fn part2(input: &str) -> usize {
 }
 
 fn minimum_cost(config: &ClawMachineConfig) -> Option<usize> {
-    let a0 = config.a.0 as i64;
-    let b0 = config.b.0 as i64;
-    let a1 = config.a.1 as i64;
-    let b1 = config.b.1 as i64;
-    let p0 = config.prize.0 as i64;
-    let p1 = config.prize.1 as i64;
-
-    let det = a0 * b1 - b0 * a1;
-    if det == 0 {
-        return None;
-    }
-
-    let a_mul_det = b1 * p0 - b0 * p1;
-    let b_mul_det = -a1 * p0 + a0 * p1;
-
-    if a_mul_det % det != 0 || b_mul_det % det != 0 {
-        return None;
-    }
+    let buttons = Matrix::from_rows([
+        vec![config.a.0 as i128, config.b.0 as i128],
+        vec![config.a.1 as i128, config.b.1 as i128],
+    ]);
 
-    let a = a_mul_det / det;
-    let b = b_mul_det / det;
+    let presses = buttons.solve_integer(&[config.prize.0 as i128, config.prize.1 as i128])?;
+    let (a, b) = (presses[0], presses[1]);
 
     if a < 0 || b < 0 {
         return None;
@@ -1,6 +1,6 @@
-use std::sync::LazyLock;
+use std::{env, fs, sync::LazyLock};
 
-use aoc2024::{get_input, Vector2D};
+use aoc2024::{get_input, Torus, TorusRobot, Vector2D};
 use regex::Regex;
 
 fn main() {
@@ -9,44 +9,35 @@ fn main() {
 
     println!("Part 1: {}", part1(&input, 101, 103));
     println!("Part 2: {}", part2);
-}
-
-struct Robot {
-    start_point: Vector2D<i32>,
-    velocity: Vector2D<i32>,
-}
 
-impl Robot {
-    fn final_location(&self, seconds: u32, width: i32, height: i32) -> Vector2D<i32> {
-        let distance_travelled = self.velocity * seconds as i32;
-        let final_location = self.start_point + distance_travelled;
-        (
-            final_location.x.rem_euclid(width),
-            final_location.y.rem_euclid(height),
-        )
-            .into()
+    // pass a path to save the picture the robots make
+    if let Some(path) = env::args().nth(1) {
+        let torus = parse(&input, 101, 103);
+        fs::write(path, torus.to_pgm(part2)).unwrap();
     }
+}
 
-    fn parse(line: &str) -> Self {
-        static LINE_REGEX: LazyLock<Regex> =
-            LazyLock::new(|| Regex::new(r"p=([0-9]+),([0-9]+) v=(-?[0-9]+),(-?[0-9]+)").unwrap());
+fn parse_robot(line: &str) -> TorusRobot {
+    static LINE_REGEX: LazyLock<Regex> =
+        LazyLock::new(|| Regex::new(r"p=([0-9]+),([0-9]+) v=(-?[0-9]+),(-?[0-9]+)").unwrap());
 
-        let (_, [p0, p1, v0, v1]) = LINE_REGEX.captures(line).unwrap().extract();
+    let (_, [p0, p1, v0, v1]) = LINE_REGEX.captures(line).unwrap().extract();
 
-        Self {
-            start_point: Vector2D::new(p0.parse().unwrap(), p1.parse().unwrap()),
-            velocity: Vector2D::new(v0.parse().unwrap(), v1.parse().unwrap()),
-        }
+    TorusRobot {
+        position: Vector2D::new(p0.parse().unwrap(), p1.parse().unwrap()),
+        velocity: Vector2D::new(v0.parse().unwrap(), v1.parse().unwrap()),
     }
 }
 
-fn part1(input: &str, width: i32, height: i32) -> u32 {
-    let robots = input.split('\n').map(Robot::parse);
+fn parse(input: &str, width: i32, height: i32) -> Torus {
+    Torus::new(width, height, input.lines().map(parse_robot).collect())
+}
 
-    let final_locations = robots.map(|robot| robot.final_location(100, width, height));
+fn part1(input: &str, width: i32, height: i32) -> u32 {
+    let torus = parse(input, width, height);
 
     let mut quadrants = [0, 0, 0, 0];
-    for final_location in final_locations {
+    for final_location in torus.positions_at(100) {
         if final_location.x < width / 2 && final_location.y < height / 2 {
             quadrants[0] += 1;
         } else if final_location.x > width / 2 && final_location.y < height / 2 {
@@ -61,56 +52,10 @@ fn part1(input: &str, width: i32, height: i32) -> u32 {
     quadrants.iter().product()
 }
 
-fn part2(input: &str, width: i32, height: i32) -> u32 {
-    let robots = input.split('\n').map(Robot::parse).collect::<Vec<_>>();
-    let mut lowest_factor = i32::MAX;
-    let mut lowest_time = 0;
-    let mut lowest_map = vec![false; (width * height) as usize];
-
-    for time in 0..10000 {
-        let final_locations = robots
-            .iter()
-            .map(|robot| robot.final_location(time, width, height));
-
-        let mut quadrants = [0, 0, 0, 0];
-        let mut map = vec![false; (width * height) as usize];
-
-        for final_location in final_locations {
-            map[(final_location.x + final_location.y * width) as usize] = true;
-
-            if final_location.x < width / 2 && final_location.y < height / 2 {
-                quadrants[0] += 1;
-            } else if final_location.x > width / 2 && final_location.y < height / 2 {
-                quadrants[1] += 1;
-            } else if final_location.x < width / 2 && final_location.y > height / 2 {
-                quadrants[2] += 1;
-            } else if final_location.x > width / 2 && final_location.y > height / 2 {
-                quadrants[3] += 1;
-            }
-        }
-
-        let factor = quadrants.iter().product::<i32>();
-
-        if factor < lowest_factor {
-            lowest_factor = factor;
-            lowest_time = time;
-            lowest_map = map;
-        }
-    }
-
-    for y in 0..height {
-        for x in 0..width {
-            if lowest_map[(x + y * width) as usize] {
-                print!("*");
-            } else {
-                print!(" ");
-            }
-        }
-        println!();
-    }
-    println!();
-
-    lowest_time
+fn part2(input: &str, width: i32, height: i32) -> u64 {
+    parse(input, width, height)
+        .most_ordered_time()
+        .expect("Width and height should be coprime")
 }
 
 #[test]
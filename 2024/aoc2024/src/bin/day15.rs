@@ -1,131 +1,111 @@
-use std::{collections::VecDeque, fmt::Debug};
+use std::{env, fmt::Debug, time::Duration};
 
-use aoc2024::{get_input, Direction, Grid2, Vector2D};
+use aoc2024::{get_input, Direction, Grid2, PushWorld, Recorder, Rgb, Vector2D, Visualizer};
 
 fn main() {
     let input = get_input(15);
     println!("Part 1: {}", part1(&input));
     println!("Part 2: {}", part2(&input));
-}
-
-#[derive(Clone, Copy, Hash, PartialEq, Eq, Default)]
-enum Space {
-    Wall,
-    Box,
-    #[default]
-    Empty,
-}
 
-#[derive(Clone, Copy, Hash, PartialEq, Eq, Default)]
-enum Space2 {
-    Wall,
-    BoxL,
-    BoxR,
-    #[default]
-    Empty,
-}
+    if let Some(mut visualizer) = Visualizer::from_args(Duration::from_millis(20)).unwrap() {
+        visualize_part2(&input, 5, &mut visualizer);
+    }
 
-impl Debug for Space2 {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Space2::Wall => write!(f, "#"),
-            Space2::BoxL => write!(f, "["),
-            Space2::BoxR => write!(f, "]"),
-            Space2::Empty => write!(f, "."),
-        }
+    // pass a path ending in .gif or .png to save an animation of part 2
+    if let Some(path) = env::args()
+        .skip(1)
+        .find(|argument| !argument.starts_with("--"))
+    {
+        let mut recorder = Recorder::new(3, 4);
+        record_part2(&input, 20, &mut recorder);
+        recorder.save(path).unwrap();
     }
 }
 
 struct Warehouse {
-    map: Grid2<Space>,
-    robot: Vector2D<i32>,
+    world: PushWorld,
+    robot: usize,
+    /// Whether the boxes are two cells wide, as in part 2
+    wide: bool,
 }
 
 impl Warehouse {
-    fn parse(input: &str) -> Self {
-        let map = Grid2::parse(input, |line| {
+    fn parse(input: &str, wide: bool) -> Self {
+        let scale = if wide { 2 } else { 1 };
+
+        let walls = Grid2::parse(input, |line| {
             line.chars()
-                .map(|c| match c {
-                    '#' => Space::Wall,
-                    'O' => Space::Box,
-                    '@' | '.' => Space::Empty,
-                    a => panic!("Don't know about an '{a}'"),
-                })
+                .flat_map(|c| std::iter::repeat_n(c == '#', scale))
                 .collect()
         });
+        let mut world = PushWorld::new(walls);
 
-        let robot_position = input.chars().position(|c| c == '@').unwrap();
-        let robot = Vector2D::new(
-            (robot_position % (map.width + 1)) as i32,
-            (robot_position / (map.width + 1)) as i32,
-        );
+        let box_shape: Vec<Vector2D<i32>> = (0..scale as i32).map(|x| (x, 0).into()).collect();
+        let mut robot = None;
 
-        Self { map, robot }
-    }
+        for (y, line) in input.lines().enumerate() {
+            for (x, c) in line.chars().enumerate() {
+                let position = Vector2D::new((x * scale) as i32, y as i32);
 
-    fn do_moves(&mut self, moves: &[Direction]) {
-        'outer: for m in moves {
-            let d: Vector2D<_> = m.into();
-
-            let mut map_copy = self.map.clone();
-            let mut thing_to_move_pos = self.robot + d;
-            let mut thing_that_is_moving = Space::Empty;
-            loop {
-                match map_copy.get::<i32>(thing_to_move_pos).unwrap() {
-                    Space::Empty => {
-                        map_copy.set::<i32>(thing_to_move_pos, thing_that_is_moving);
-                        break;
-                    }
-                    Space::Wall => {
-                        // we're now stuck
-                        continue 'outer;
-                    }
-                    Space::Box => {
-                        map_copy.set::<i32>(thing_to_move_pos, thing_that_is_moving);
-                        thing_that_is_moving = Space::Box;
-                        thing_to_move_pos += d;
+                match c {
+                    'O' => {
+                        world.add_object(position, box_shape.clone());
                     }
+                    '@' => robot = Some(world.add_object(position, vec![(0, 0).into()])),
+                    '#' | '.' => {}
+                    a => panic!("Don't know about an '{a}'"),
                 }
             }
+        }
 
-            self.map = map_copy;
-            self.robot += d;
+        Self {
+            world,
+            robot: robot.expect("There should be a robot"),
+            wide,
+        }
+    }
+
+    fn do_moves(&mut self, moves: &[Direction]) {
+        for &m in moves {
+            self.world.push(self.robot, m);
         }
     }
 
     fn gps(&self) -> u32 {
-        self.map
+        self.world
+            .objects()
             .iter()
-            .filter_map(|(pos, &thing)| {
-                if thing == Space::Box {
-                    Some(pos.x as u32 + pos.y as u32 * 100)
-                } else {
-                    None
-                }
-            })
+            .enumerate()
+            .filter(|&(id, _)| id != self.robot)
+            .map(|(_, object)| object.position.x as u32 + object.position.y as u32 * 100)
             .sum()
     }
+
+    fn tile(&self, x: usize, y: usize) -> (char, Rgb) {
+        let point = Vector2D::new(x as i32, y as i32);
+
+        match self.world.object_at(point) {
+            Some(id) if id == self.robot => ('@', [230, 60, 60]),
+            Some(_) if !self.wide => ('O', [200, 150, 80]),
+            Some(id) if self.world.object(id).position == point => ('[', [200, 150, 80]),
+            Some(_) => (']', [200, 150, 80]),
+            None if self.world.is_wall(point) => ('#', [110, 110, 120]),
+            None => ('.', [20, 20, 30]),
+        }
+    }
+
+    fn capture(&self, recorder: &mut Recorder) {
+        recorder.capture(self.world.width(), self.world.height(), |x, y| {
+            self.tile(x, y).1
+        });
+    }
 }
 
 impl Debug for Warehouse {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        for y in 0..self.map.height as i32 {
-            for x in 0..self.map.width as i32 {
-                let space = *self.map.get::<i32>((x, y)).unwrap();
-
-                if self.robot == (x, y).into() {
-                    write!(f, "@")?;
-                } else {
-                    write!(
-                        f,
-                        "{}",
-                        match space {
-                            Space::Wall => '#',
-                            Space::Box => 'O',
-                            Space::Empty => '.',
-                        }
-                    )?;
-                }
+        for y in 0..self.world.height() {
+            for x in 0..self.world.width() {
+                write!(f, "{}", self.tile(x, y).0)?;
             }
 
             writeln!(f)?;
@@ -135,204 +115,58 @@ impl Debug for Warehouse {
     }
 }
 
-fn part1(input: &str) -> u32 {
+fn parse(input: &str, wide: bool) -> (Warehouse, Vec<Direction>) {
     let (map, directions) = input.split_once("\n\n").unwrap();
 
-    let mut map = Warehouse::parse(map);
     let directions = directions
         .chars()
         .filter_map(Direction::parse)
         .collect::<Vec<_>>();
 
+    (Warehouse::parse(map, wide), directions)
+}
+
+fn part1(input: &str) -> u32 {
+    let (mut map, directions) = parse(input, false);
+
     map.do_moves(&directions);
     map.gps()
 }
 
-struct Warehouse2 {
-    map: Grid2<Space2>,
-    robot: Vector2D<i32>,
-}
+/// Shows the warehouse every `moves_per_frame` moves of the robot
+fn visualize_part2(input: &str, moves_per_frame: usize, visualizer: &mut Visualizer) {
+    let (mut map, directions) = parse(input, true);
 
-impl Warehouse2 {
-    fn parse(input: &str) -> Self {
-        let map = Grid2::parse(input, |line| {
-            line.chars()
-                .flat_map(|c| match c {
-                    '#' => [Space2::Wall, Space2::Wall],
-                    'O' => [Space2::BoxL, Space2::BoxR],
-                    '@' | '.' => [Space2::Empty, Space2::Empty],
-                    a => panic!("Don't know about an '{a}'"),
-                })
-                .collect()
-        });
+    for (i, chunk) in directions.chunks(moves_per_frame).enumerate() {
+        map.do_moves(chunk);
 
-        let robot_position = input.chars().position(|c| c == '@').unwrap();
-        let robot = Vector2D::new(
-            (robot_position % (map.width / 2 + 1)) as i32 * 2,
-            (robot_position / (map.width / 2 + 1)) as i32,
+        let caption = format!(
+            "move {} of {}, gps {}",
+            (i * moves_per_frame + chunk.len()),
+            directions.len(),
+            map.gps()
         );
-
-        Self { map, robot }
-    }
-
-    fn do_moves(&mut self, moves: &[Direction]) {
-        'outer: for m in moves {
-            let d: Vector2D<_> = m.into();
-
-            let mut map_copy = self.map.clone();
-
-            struct ThingToMove {
-                thing_that_is_moving: Space2,
-                move_into: Vector2D<i32>,
-            }
-
-            impl Debug for ThingToMove {
-                fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-                    write!(
-                        f,
-                        "{:?} -> ({}, {})",
-                        self.thing_that_is_moving, self.move_into.x, self.move_into.y
-                    )
-                }
-            }
-
-            let mut things_to_move = VecDeque::from([ThingToMove {
-                thing_that_is_moving: Space2::Empty,
-                move_into: self.robot + d,
-            }]);
-
-            while let Some(thing_to_move) = things_to_move.pop_front() {
-                match map_copy.get::<i32>(thing_to_move.move_into).unwrap() {
-                    Space2::Empty => {
-                        map_copy.set::<i32>(
-                            thing_to_move.move_into,
-                            thing_to_move.thing_that_is_moving,
-                        );
-                    }
-                    Space2::Wall => {
-                        // we're now stuck
-                        continue 'outer;
-                    }
-                    Space2::BoxL => {
-                        map_copy.set::<i32>(
-                            thing_to_move.move_into,
-                            thing_to_move.thing_that_is_moving,
-                        );
-                        map_copy.set::<i32>(thing_to_move.move_into + (1, 0).into(), Space2::Empty);
-
-                        match *m {
-                            Direction::North | Direction::South | Direction::East => {
-                                things_to_move.push_back(ThingToMove {
-                                    thing_that_is_moving: Space2::BoxR,
-                                    move_into: thing_to_move.move_into + d + (1, 0).into(),
-                                });
-                                things_to_move.push_back(ThingToMove {
-                                    thing_that_is_moving: Space2::BoxL,
-                                    move_into: thing_to_move.move_into + d,
-                                });
-                            }
-                            Direction::West => {
-                                things_to_move.push_back(ThingToMove {
-                                    thing_that_is_moving: Space2::BoxL,
-                                    move_into: thing_to_move.move_into + d,
-                                });
-                                things_to_move.push_back(ThingToMove {
-                                    thing_that_is_moving: Space2::BoxR,
-                                    move_into: thing_to_move.move_into + d + (1, 0).into(),
-                                });
-                            }
-                        }
-                    }
-                    Space2::BoxR => {
-                        map_copy.set::<i32>(
-                            thing_to_move.move_into,
-                            thing_to_move.thing_that_is_moving,
-                        );
-                        map_copy
-                            .set::<i32>(thing_to_move.move_into + (-1, 0).into(), Space2::Empty);
-
-                        match *m {
-                            Direction::North | Direction::South | Direction::East => {
-                                things_to_move.push_back(ThingToMove {
-                                    thing_that_is_moving: Space2::BoxR,
-                                    move_into: thing_to_move.move_into + d,
-                                });
-                                things_to_move.push_back(ThingToMove {
-                                    thing_that_is_moving: Space2::BoxL,
-                                    move_into: thing_to_move.move_into + d + (-1, 0).into(),
-                                });
-                            }
-                            Direction::West => {
-                                things_to_move.push_back(ThingToMove {
-                                    thing_that_is_moving: Space2::BoxL,
-                                    move_into: thing_to_move.move_into + d + (-1, 0).into(),
-                                });
-                                things_to_move.push_back(ThingToMove {
-                                    thing_that_is_moving: Space2::BoxR,
-                                    move_into: thing_to_move.move_into + d,
-                                });
-                            }
-                        }
-                    }
-                }
-            }
-
-            self.map = map_copy;
-            self.robot += d;
-        }
-    }
-
-    fn gps(&self) -> u32 {
-        self.map
-            .iter()
-            .filter_map(|(pos, &thing)| {
-                if thing == Space2::BoxL {
-                    Some(pos.x as u32 + pos.y as u32 * 100)
-                } else {
-                    None
-                }
+        visualizer
+            .frame(&caption, map.world.width(), map.world.height(), |x, y| {
+                map.tile(x, y)
             })
-            .sum()
+            .unwrap();
     }
 }
 
-impl Debug for Warehouse2 {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        for y in 0..self.map.height as i32 {
-            for x in 0..self.map.width as i32 {
-                let space = *self.map.get::<i32>((x, y)).unwrap();
-
-                if self.robot == (x, y).into() {
-                    write!(f, "@")?;
-                } else {
-                    write!(
-                        f,
-                        "{}",
-                        match space {
-                            Space2::Wall => '#',
-                            Space2::BoxL => '[',
-                            Space2::BoxR => ']',
-                            Space2::Empty => '.',
-                        }
-                    )?;
-                }
-            }
+/// Captures a frame every `moves_per_frame` moves of the robot
+fn record_part2(input: &str, moves_per_frame: usize, recorder: &mut Recorder) {
+    let (mut map, directions) = parse(input, true);
 
-            writeln!(f)?;
-        }
-
-        Ok(())
+    map.capture(recorder);
+    for chunk in directions.chunks(moves_per_frame) {
+        map.do_moves(chunk);
+        map.capture(recorder);
     }
 }
 
 fn part2(input: &str) -> u32 {
-    let (map, directions) = input.split_once("\n\n").unwrap();
-
-    let mut map = Warehouse2::parse(map);
-    let directions = directions
-        .chars()
-        .filter_map(Direction::parse)
-        .collect::<Vec<_>>();
+    let (mut map, directions) = parse(input, true);
 
     map.do_moves(&directions);
     map.gps()
@@ -1,20 +1,25 @@
 use std::{
     cmp::Reverse,
     collections::{BinaryHeap, HashMap, HashSet},
+    time::Duration,
 };
 
-use aoc2024::{get_input, Direction, Grid2, Vector2D};
+use aoc2024::{get_input, Direction, Grid2, Rgb, Vector2D, Visualizer};
+
+/// How many nodes to explore between frames when visualizing
+const NODES_PER_FRAME: usize = 500;
 
 fn main() {
     let input = get_input(16);
-    let (part1, part2) = reindeer_race(&input);
+    let mut visualizer = Visualizer::from_args(Duration::from_millis(30)).unwrap();
+    let (part1, part2) = reindeer_race(&input, visualizer.as_mut());
     println!("Part 1: {}", part1);
     println!("Part 2: {}", part2);
 
     // 582 too high, 523 too low
 }
 
-fn reindeer_race(input: &str) -> (u32, usize) {
+fn reindeer_race(input: &str, mut visualizer: Option<&mut Visualizer>) -> (u32, usize) {
     let map = Grid2::parse(input, |line| line.chars().map(|c| c == '#').collect());
     let end = input.chars().position(|c| c == 'E').unwrap();
     let start = input.chars().position(|c| c == 'S').unwrap();
@@ -47,8 +52,30 @@ fn reindeer_race(input: &str) -> (u32, usize) {
     }));
 
     let mut end_pos_dir = None;
+    let mut explored = Grid2::new(map.width, map.height);
+
+    for explored_count in 0.. {
+        let Some(Reverse(minimum)) = q.pop() else {
+            break;
+        };
+
+        if let Some(visualizer) = visualizer.as_deref_mut() {
+            explored.set::<i32>(minimum.point, true);
+
+            if explored_count % NODES_PER_FRAME == 0 {
+                let caption = format!("explored {explored_count}, score {}", minimum.distance);
+                visualizer
+                    .frame(&caption, map.width, map.height, |x, y| {
+                        if minimum.point == (x as i32, y as i32) {
+                            ('@', REINDEER)
+                        } else {
+                            maze_tile(&map, &explored, x, y)
+                        }
+                    })
+                    .unwrap();
+            }
+        }
 
-    while let Some(Reverse(minimum)) = q.pop() {
         if minimum.point == (end.x, end.y) && end_pos_dir.is_none() {
             end_pos_dir = Some((minimum.point, minimum.direction));
         }
@@ -153,9 +180,35 @@ fn reindeer_race(input: &str) -> (u32, usize) {
 
     collect_best_seats(end_pos_dir.unwrap(), start, &distance, &mut best_seats);
 
+    if let Some(visualizer) = visualizer {
+        let caption = format!("score {end_distance}, {} best seats", best_seats.len());
+        visualizer
+            .frame(&caption, map.width, map.height, |x, y| {
+                if best_seats.contains(&(x as i32, y as i32)) {
+                    ('O', BEST_SEAT)
+                } else {
+                    maze_tile(&map, &explored, x, y)
+                }
+            })
+            .unwrap();
+    }
+
     (end_distance, best_seats.len())
 }
 
+const REINDEER: Rgb = [230, 60, 60];
+const BEST_SEAT: Rgb = [240, 200, 60];
+
+fn maze_tile(walls: &Grid2<bool>, explored: &Grid2<bool>, x: usize, y: usize) -> (char, Rgb) {
+    if walls.points[y][x] {
+        ('#', [110, 110, 120])
+    } else if explored.points[y][x] {
+        ('.', [60, 120, 200])
+    } else {
+        (' ', [20, 20, 30])
+    }
+}
+
 #[test]
 fn given_input() {
     let input = "###############
@@ -174,7 +227,7 @@ fn given_input() {
 #S..#.....#...#
 ###############";
 
-    assert_eq!(reindeer_race(input), (7036, 45));
+    assert_eq!(reindeer_race(input, None), (7036, 45));
 }
 
 #[test]
@@ -197,5 +250,17 @@ fn given_input2() {
 #S#.............#
 #################";
 
-    assert_eq!(reindeer_race(input), (11048, 64));
+    assert_eq!(reindeer_race(input, None), (11048, 64));
+}
+
+#[test]
+fn visualizing_gives_the_same_answer() {
+    let input = "#####
+#..E#
+#.#.#
+#S..#
+#####";
+
+    let mut visualizer = Visualizer::plain(std::io::sink());
+    assert_eq!(reindeer_race(input, Some(&mut visualizer)), (1004, 5));
 }
@@ -0,0 +1,159 @@
+//! An interactive debugger for the 3-bit computer from day 17.
+//!
+//! Loads the puzzle input, or the file given as the first argument, and reads
+//! commands from stdin. Type `help` to list them.
+
+use std::{
+    env, fs,
+    io::{self, BufRead, Write},
+};
+
+use aoc2024::{get_input, StepResult, StopReason, ThreeBitComputer};
+
+const HELP: &str = "\
+step [n]         execute n instructions (default 1)
+continue         run until a breakpoint or the program halts
+break <addr>     toggle a breakpoint before the instruction at addr
+regs             show the registers and the next instruction
+set <reg> <val>  set register a, b, c or pc
+reset [a]        go back to the start, keeping a or setting it
+trace [n]        execute up to n instructions (default 20), showing each one
+list             disassemble the program
+output           show everything output so far
+quit             exit";
+
+fn main() -> anyhow::Result<()> {
+    let input = match env::args().nth(1) {
+        Some(path) => fs::read_to_string(path)?,
+        None => get_input(17),
+    };
+
+    let mut computer = ThreeBitComputer::parse(&input);
+    let initial = computer.clone();
+    let mut output = vec![];
+
+    print_registers(&computer);
+
+    let stdin = io::stdin();
+    loop {
+        print!("> ");
+        io::stdout().flush()?;
+
+        let mut line = String::new();
+        if stdin.lock().read_line(&mut line)? == 0 {
+            break;
+        }
+
+        let mut words = line.split_whitespace();
+        let Some(command) = words.next() else {
+            continue;
+        };
+        let argument = words.next();
+
+        match command {
+            "s" | "step" => {
+                let count = argument.and_then(|n| n.parse().ok()).unwrap_or(1);
+                for _ in 0..count {
+                    match computer.step() {
+                        StepResult::Continue => {}
+                        StepResult::Output(value) => {
+                            println!("output {value}");
+                            output.push(value);
+                        }
+                        StepResult::Halted => {
+                            println!("halted");
+                            break;
+                        }
+                    }
+                }
+
+                print_registers(&computer);
+            }
+            "c" | "continue" => {
+                let start = output.len();
+                let reason = computer.resume(&mut output);
+
+                if output.len() > start {
+                    println!("output {:?}", &output[start..]);
+                }
+
+                match reason {
+                    StopReason::Breakpoint(address) => println!("breakpoint at {address}"),
+                    StopReason::Halted => println!("halted"),
+                }
+
+                print_registers(&computer);
+            }
+            "b" | "break" => match argument.and_then(|address| address.parse().ok()) {
+                Some(address) => {
+                    if computer.breakpoints.remove(&address) {
+                        println!("removed breakpoint at {address}");
+                    } else {
+                        computer.breakpoints.insert(address);
+                        println!("added breakpoint at {address}");
+                    }
+                }
+                None => println!("breakpoints: {:?}", computer.breakpoints),
+            },
+            "r" | "regs" => print_registers(&computer),
+            "set" => {
+                let value = words.next().and_then(|value| value.parse().ok());
+                match (argument, value) {
+                    (Some("a"), Some(value)) => computer.a = value,
+                    (Some("b"), Some(value)) => computer.b = value,
+                    (Some("c"), Some(value)) => computer.c = value,
+                    (Some("pc"), Some(value)) => computer.pc = value,
+                    _ => println!("usage: set <a|b|c|pc> <value>"),
+                }
+
+                print_registers(&computer);
+            }
+            "reset" => {
+                let a = argument.and_then(|a| a.parse().ok()).unwrap_or(initial.a);
+                computer.reset(a, initial.b, initial.c);
+                output.clear();
+
+                print_registers(&computer);
+            }
+            "t" | "trace" => {
+                let count = argument.and_then(|n| n.parse().ok()).unwrap_or(20);
+                for entry in computer.trace(count) {
+                    print!(
+                        "{:3}: {:<8} a={} b={} c={}",
+                        entry.pc,
+                        entry.instruction.to_string(),
+                        entry.a,
+                        entry.b,
+                        entry.c
+                    );
+
+                    if let Some(value) = entry.output {
+                        print!(" output {value}");
+                        output.push(value);
+                    }
+
+                    println!();
+                }
+            }
+            "l" | "list" => print!("{}", ThreeBitComputer::disassemble(&computer.program)),
+            "o" | "output" => println!("{output:?}"),
+            "q" | "quit" => break,
+            "h" | "help" => println!("{HELP}"),
+            _ => println!("unknown command {command}, try help"),
+        }
+    }
+
+    Ok(())
+}
+
+fn print_registers(computer: &ThreeBitComputer) {
+    print!(
+        "a={} (0o{:o}) b={} c={} pc={}",
+        computer.a, computer.a, computer.b, computer.c, computer.pc
+    );
+
+    match computer.current_instruction() {
+        Some(instruction) => println!(" next: {instruction}"),
+        None => println!(" halted"),
+    }
+}
@@ -1,100 +1,197 @@
-use std::{cmp::Reverse, collections::HashMap};
+use std::{
+    collections::{BTreeSet, HashMap},
+    env, fmt, fs, iter,
+};
 
-use aoc2024::get_input;
+use aoc2024::{get_input, read_bus, Circuit, GateKind};
 use itertools::Itertools;
+use rand::{rngs::StdRng, Rng, SeedableRng};
 
 fn main() {
     let input = get_input(24);
     println!("Part 1: {}", part1(&input));
+    match part2(&input) {
+        Ok(swapped) => println!("Part 2: {swapped}"),
+        Err(error) => println!("Part 2: can't solve, {error}"),
+    }
+
+    // pass a path to write out the circuit for viewing with graphviz
+    if let Some(path) = env::args().nth(1) {
+        fs::write(path, System::parse(&input).circuit.to_dot()).unwrap();
+    }
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, Debug)]
-enum Gate {
-    And,
-    Or,
-    Xor,
+/// Why the swapped outputs couldn't be found
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum SwapError {
+    /// Every swap moves two outputs, so an odd number of faulty outputs means
+    /// something other than swaps is wrong with the adder
+    OddFaults(Vec<String>),
+    /// None of the ways of swapping the faulty outputs fixes the adder
+    NoFix(Vec<String>),
 }
 
-impl Gate {
-    fn apply(self, a: bool, b: bool) -> bool {
+impl fmt::Display for SwapError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Gate::And => a && b,
-            Gate::Or => a || b,
-            Gate::Xor => a ^ b,
+            SwapError::OddFaults(faults) => write!(
+                f,
+                "an odd number of faulty outputs ({}) can't be paired up into swaps",
+                faults.join(",")
+            ),
+            SwapError::NoFix(faults) => write!(
+                f,
+                "no way of swapping the faulty outputs ({}) gives a working adder",
+                faults.join(",")
+            ),
         }
     }
 }
 
-struct Connection<'a> {
-    input1: &'a str,
-    input2: &'a str,
-    gate: Gate,
-    output: &'a str,
-}
-
-struct System<'a> {
-    gates: Vec<Connection<'a>>,
+#[derive(Clone)]
+struct System {
+    circuit: Circuit,
 }
 
-impl<'a> System<'a> {
-    fn parse(input: &'a str) -> Self {
+impl System {
+    fn parse(input: &str) -> Self {
         let (_, gates) = input.split_once("\n\n").unwrap();
 
-        let gate_regex = regex::Regex::new("(\\w+) (XOR|OR|AND) (\\w+) -> (\\w+)").unwrap();
-
-        let gates = gates
-            .split('\n')
-            .map(|gate| {
-                let (_, [first, gate, second, output]) =
-                    gate_regex.captures(gate).unwrap().extract();
-
-                let gate = match gate {
-                    "AND" => Gate::And,
-                    "OR" => Gate::Or,
-                    "XOR" => Gate::Xor,
-                    _ => panic!("Unknown gate {gate}"),
-                };
-
-                Connection {
-                    input1: first,
-                    input2: second,
-                    gate,
-                    output,
-                }
-            })
+        Self {
+            circuit: Circuit::parse(gates),
+        }
+    }
+
+    fn execute(&self, values: &HashMap<&str, bool>) -> u64 {
+        let inputs: Vec<_> = values
+            .iter()
+            .filter_map(|(name, &value)| Some((self.circuit.wire_index(name)?, value)))
             .collect();
 
-        Self { gates }
+        let values = self.circuit.evaluate(&inputs).expect("Circuit has a loop");
+
+        // collect all the z's
+        read_bus(&values, &self.circuit.bus("z")).expect("Not every z wire has a value")
+    }
+
+    /// The number of bits in each of the x and y inputs
+    fn input_bits(&self) -> usize {
+        self.circuit.bus("x").len()
+    }
+
+    /// Runs the circuit with the given numbers on the x and y wires, returning
+    /// `None` if the wiring doesn't give an answer
+    fn add(&self, x: u64, y: u64) -> Option<u64> {
+        self.circuit.evaluate_buses(&[("x", x), ("y", y)], "z")
+    }
+
+    /// The same system with the outputs of two gates swapped
+    fn with_swapped(&self, a: &str, b: &str) -> Self {
+        let mut system = self.clone();
+        let wire = |name| {
+            self.circuit
+                .wire_index(name)
+                .unwrap_or_else(|| panic!("No wire named {name}"))
+        };
+
+        system.circuit.swap_outputs(wire(a), wire(b));
+        system
     }
 
-    fn execute(&self, mut values: HashMap<&'a str, bool>) -> u64 {
-        loop {
-            let mut did_something = false;
+    /// Checks the structure against a ripple-carry adder, where bit `i` is
+    ///
+    ///   x_i XOR y_i -> s_i        x_i AND y_i -> d_i
+    ///   s_i XOR c_i -> z_i        s_i AND c_i -> e_i
+    ///   d_i OR e_i  -> c_(i+1)
+    ///
+    /// with bit 0 being a half adder, and the final carry being the top z
+    /// wire. Returns the outputs of every gate which doesn't fit.
+    fn adder_faults(&self) -> BTreeSet<String> {
+        let circuit = &self.circuit;
+        let bits = self.input_bits();
+        let last_z = format!("z{bits:02}");
 
-            for gate in &self.gates {
-                let input1 = values.get(gate.input1).copied();
-                let input2 = values.get(gate.input2).copied();
-                let output = values.get(gate.output).copied();
+        let is_input = |wire: usize| {
+            let name = circuit.wire_name(wire);
+            name.starts_with('x') || name.starts_with('y')
+        };
+        let is_first_bit = |inputs: [usize; 2]| {
+            let names = inputs.map(|wire| circuit.wire_name(wire));
+            matches!(names, ["x00", "y00"] | ["y00", "x00"])
+        };
+        let feeds_into = |wire: usize, kind: GateKind| {
+            circuit
+                .consumers(wire)
+                .any(|consumer| consumer.kind == kind)
+        };
 
-                if let (Some(input1), Some(input2), None) = (input1, input2, output) {
-                    values.insert(gate.output, gate.gate.apply(input1, input2));
-                    did_something = true;
+        let mut faults = BTreeSet::new();
+
+        for gate in circuit.gates() {
+            let output = circuit.wire_name(gate.output);
+            let from_inputs = gate.inputs.iter().all(|&input| is_input(input));
+
+            let fits = if output.starts_with('z') && output != last_z {
+                // every sum bit is an XOR
+                gate.kind == GateKind::Xor && (!from_inputs || is_first_bit(gate.inputs))
+            } else if output == last_z {
+                // the final carry out
+                gate.kind == GateKind::Or
+            } else {
+                match gate.kind {
+                    // s_i, which must be combined with the carry
+                    GateKind::Xor => from_inputs && feeds_into(gate.output, GateKind::Xor),
+                    // d_i and e_i feed the carry, except for bit 0 where d_0 is the carry
+                    GateKind::And if is_first_bit(gate.inputs) => {
+                        feeds_into(gate.output, GateKind::Xor)
+                    }
+                    GateKind::And => feeds_into(gate.output, GateKind::Or),
+                    // c_(i+1), which is the carry into the next bit
+                    GateKind::Or => {
+                        feeds_into(gate.output, GateKind::Xor)
+                            && feeds_into(gate.output, GateKind::And)
+                    }
                 }
-            }
+            };
 
-            if !did_something {
-                break;
+            if !fits {
+                faults.insert(output.to_string());
             }
         }
 
-        // collect all the z's
-        values
-            .into_iter()
-            .filter(|(k, _)| k.starts_with('z'))
-            .sorted_by_key(|kv| Reverse(kv.0))
-            .fold(0u64, |curr, (_, next)| {
-                (curr << 1) | if next { 1 } else { 0 }
+        faults
+    }
+
+    /// Checks that the circuit adds random numbers correctly
+    fn verify_addition(&self, trials: usize, seed: u64) -> bool {
+        let bits = self.input_bits();
+        let mask = (1 << bits) - 1;
+        let mut rng = StdRng::seed_from_u64(seed);
+
+        (0..trials).all(|_| {
+            let (x, y) = (rng.gen::<u64>() & mask, rng.gen::<u64>() & mask);
+            self.add(x, y) == Some(x + y)
+        })
+    }
+
+    /// Finds which pairs of gates have had their outputs swapped, by trying
+    /// every way of pairing up the faulty outputs until the circuit both looks
+    /// and behaves like an adder
+    fn find_swaps(&self) -> Result<Vec<(String, String)>, SwapError> {
+        let faults: Vec<_> = self.adder_faults().into_iter().collect();
+        if faults.len() % 2 == 1 {
+            return Err(SwapError::OddFaults(faults));
+        }
+
+        pairings(faults.clone())
+            .find(|pairs| {
+                let fixed = pairs
+                    .iter()
+                    .fold(self.clone(), |system, (a, b)| system.with_swapped(a, b));
+
+                fixed.adder_faults().is_empty() && fixed.verify_addition(100, 24)
             })
+            .ok_or(SwapError::NoFix(faults))
     }
 }
 
@@ -110,7 +207,38 @@ fn part1(input: &str) -> u64 {
 
     let system = System::parse(input);
 
-    system.execute(values)
+    system.execute(&values)
+}
+
+fn part2(input: &str) -> Result<String, SwapError> {
+    let system = System::parse(input);
+    let swaps = system.find_swaps()?;
+
+    Ok(swaps
+        .into_iter()
+        .flat_map(|(a, b)| [a, b])
+        .sorted()
+        .join(","))
+}
+
+/// Every way of splitting `items` into pairs, generated one at a time since
+/// there are `(n - 1)!!` of them. Gives nothing if there are an odd number of items.
+fn pairings<T: Clone + 'static>(items: Vec<T>) -> Box<dyn Iterator<Item = Vec<(T, T)>>> {
+    let Some((first, rest)) = items.split_first() else {
+        return Box::new(iter::once(vec![]));
+    };
+
+    let (first, rest) = (first.clone(), rest.to_vec());
+    Box::new((0..rest.len()).flat_map(move |i| {
+        let mut others = rest.clone();
+        let partner = others.remove(i);
+        let first = first.clone();
+
+        pairings(others).map(move |mut pairs| {
+            pairs.push((first.clone(), partner.clone()));
+            pairs
+        })
+    }))
 }
 
 #[test]
@@ -165,3 +293,78 @@ tnw OR pbm -> gnj";
 
     assert_eq!(part1(input), 2024);
 }
+
+#[cfg(test)]
+fn ripple_carry_adder(bits: usize) -> String {
+    let mut gates = vec![
+        "x00 XOR y00 -> z00".to_string(),
+        "x00 AND y00 -> c01".to_string(),
+    ];
+
+    for i in 1..bits {
+        let carry_out = if i == bits - 1 {
+            format!("z{bits:02}")
+        } else {
+            format!("c{:02}", i + 1)
+        };
+
+        gates.extend([
+            format!("x{i:02} XOR y{i:02} -> s{i:02}"),
+            format!("x{i:02} AND y{i:02} -> d{i:02}"),
+            format!("s{i:02} XOR c{i:02} -> z{i:02}"),
+            format!("c{i:02} AND s{i:02} -> e{i:02}"),
+            format!("d{i:02} OR e{i:02} -> {carry_out}"),
+        ]);
+    }
+
+    format!("x00: 0\n\n{}", gates.join("\n"))
+}
+
+#[test]
+fn finds_swapped_adder_outputs() {
+    let system = System::parse(&ripple_carry_adder(8));
+
+    assert!(system.adder_faults().is_empty());
+    assert!(system.verify_addition(100, 1));
+    assert_eq!(system.add(200, 100), Some(300));
+
+    let broken = system.with_swapped("s03", "d03").with_swapped("z05", "e05");
+    assert_eq!(
+        broken.adder_faults().into_iter().collect::<Vec<_>>(),
+        vec!["d03", "e05", "s03", "z05"]
+    );
+    assert!(!broken.verify_addition(100, 1));
+
+    let mut swaps = broken.find_swaps().unwrap();
+    swaps.sort();
+    assert_eq!(
+        swaps,
+        [("d03", "s03"), ("e05", "z05")].map(|(a, b)| (a.to_string(), b.to_string()))
+    );
+}
+
+#[test]
+fn pairs_up_lazily() {
+    assert_eq!(pairings(vec![1, 2, 3, 4, 5, 6]).count(), 15);
+    assert_eq!(pairings(vec![1, 2, 3]).count(), 0);
+    assert_eq!(pairings(vec![1, 2]).collect::<Vec<_>>(), vec![vec![(1, 2)]]);
+
+    // sixteen faults have over 2 million pairings, so only the first few are built
+    assert!(pairings((0..16).collect()).nth(1000).is_some());
+}
+
+#[test]
+fn odd_number_of_faults() {
+    // an OR where there should be an AND is one fault which no swap can fix
+    let system =
+        System::parse(&ripple_carry_adder(8).replace("x03 AND y03 -> d03", "x03 OR y03 -> d03"));
+
+    assert_eq!(
+        system.find_swaps(),
+        Err(SwapError::OddFaults(vec!["d03".to_string()]))
+    );
+    assert_eq!(
+        system.find_swaps().unwrap_err().to_string(),
+        "an odd number of faulty outputs (d03) can't be paired up into swaps"
+    );
+}
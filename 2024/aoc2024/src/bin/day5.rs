@@ -1,4 +1,4 @@
-use std::collections::HashSet;
+use aoc2024::OrderingRules;
 
 fn main() {
     let puzzle_input = aoc2024::get_input(5);
@@ -8,7 +8,7 @@ fn main() {
 }
 
 struct Input {
-    orderings: HashSet<(usize, usize)>,
+    orderings: OrderingRules<usize>,
     updates: Vec<Vec<usize>>,
 }
 
@@ -16,16 +16,13 @@ impl Input {
     fn parse(input: &str) -> Self {
         let (orderings, updates) = input.split_once("\n\n").unwrap();
 
-        let orderings = orderings
-            .split('\n')
-            .map(|o| {
-                let (before, after) = o.split_once('|').unwrap();
-                (
-                    before.parse::<usize>().unwrap(),
-                    after.parse::<usize>().unwrap(),
-                )
-            })
-            .collect::<HashSet<_>>();
+        let orderings = OrderingRules::new(orderings.split('\n').map(|o| {
+            let (before, after) = o.split_once('|').unwrap();
+            (
+                before.parse::<usize>().unwrap(),
+                after.parse::<usize>().unwrap(),
+            )
+        }));
 
         let updates = updates
             .split('\n')
@@ -51,20 +48,9 @@ fn part2(input: &Input) -> usize {
     input
         .updates
         .iter()
-        .filter(|update| !update_is_in_order(update, &input.orderings))
+        .filter(|update| !input.orderings.is_sorted(update))
         .map(|incorrect| {
-            let mut working = incorrect.to_vec();
-
-            while !update_is_in_order(&working, &input.orderings) {
-                for i in 0..incorrect.len() {
-                    for j in i + 1..incorrect.len() {
-                        if input.orderings.contains(&(working[j], working[i])) {
-                            working.swap(i, j);
-                        }
-                    }
-                }
-            }
-
+            let working = input.orderings.sorted(incorrect).unwrap();
             working[working.len() / 2]
         })
         .sum()
@@ -74,19 +60,7 @@ fn valid_updates(input: &Input) -> impl Iterator<Item = &'_ Vec<usize>> {
     input
         .updates
         .iter()
-        .filter(|update| update_is_in_order(update, &input.orderings))
-}
-
-fn update_is_in_order(update: &[usize], ordering: &HashSet<(usize, usize)>) -> bool {
-    for i in 0..update.len() {
-        for j in i + 1..update.len() {
-            if ordering.contains(&(update[j], update[i])) {
-                return false;
-            }
-        }
-    }
-
-    true
+        .filter(|update| input.orderings.is_sorted(update))
 }
 
 #[test]
@@ -1,11 +1,18 @@
-use std::collections::HashSet;
+use std::{collections::HashSet, env, iter};
 
-use aoc2024::{get_input, Direction, Grid2, Vector2D};
+use aoc2024::{get_input, Direction, Grid2, Recorder, Rgb, Vector2D};
 
 fn main() {
     let input = Map::parse(&get_input(6));
     println!("Part 1: {}", positions(&input).len());
     println!("Part 2: {}", additional_obstruction_locations(&input));
+
+    // pass a path ending in .gif or .png to save an animation of the guard's patrol
+    if let Some(path) = env::args().nth(1) {
+        let mut recorder = Recorder::new(3, 4);
+        record_patrol(&input, 10, &mut recorder);
+        recorder.save(path).unwrap();
+    }
 }
 
 #[derive(Clone)]
@@ -47,17 +54,19 @@ impl Map {
     }
 }
 
-fn does_route_loop(input: &Map) -> bool {
+/// The guard's position and the direction they're facing at each step, until
+/// they leave the map
+fn patrol(input: &Map) -> impl Iterator<Item = (Vector2D<i32>, Direction)> + '_ {
     let mut current_point = input.position;
     let mut current_direction = input.direction;
 
-    let mut visited_points = HashSet::new();
-
-    while input.contains_point(current_point) {
-        if !visited_points.insert((current_point, current_direction)) {
-            return true;
+    iter::from_fn(move || {
+        if !input.contains_point(current_point) {
+            return None;
         }
 
+        let step = (current_point, current_direction);
+
         loop {
             let potential_new_point = current_point + current_direction.into();
 
@@ -68,33 +77,57 @@ fn does_route_loop(input: &Map) -> bool {
 
             current_direction = current_direction.rotate_right();
         }
-    }
 
-    false
+        Some(step)
+    })
 }
 
-fn positions(input: &Map) -> HashSet<Vector2D<i32>> {
-    let mut current_point = input.position;
-    let mut current_direction = input.direction;
-
+fn does_route_loop(input: &Map) -> bool {
     let mut visited_points = HashSet::new();
+    patrol(input).any(|step| !visited_points.insert(step))
+}
 
-    while input.contains_point(current_point) {
-        visited_points.insert(current_point);
-
-        loop {
-            let potential_new_point = current_point + current_direction.into();
-
-            if !input.is_obstructed(potential_new_point) {
-                current_point = potential_new_point;
-                break;
-            }
+fn positions(input: &Map) -> HashSet<Vector2D<i32>> {
+    patrol(input).map(|(point, _)| point).collect()
+}
 
-            current_direction = current_direction.rotate_right();
+/// Captures a frame every `steps_per_frame` steps, showing where the guard has been
+fn record_patrol(input: &Map, steps_per_frame: usize, recorder: &mut Recorder) {
+    const GUARD: Rgb = [230, 60, 60];
+    const VISITED: Rgb = [60, 120, 200];
+    const OBSTRUCTION: Rgb = [110, 110, 120];
+    const EMPTY: Rgb = [20, 20, 30];
+
+    let (width, height) = (input.obstructions.width, input.obstructions.height);
+    let mut visited = Grid2::new(width, height);
+
+    for (i, (point, _)) in patrol(input).enumerate() {
+        visited.set::<i32>(point, true);
+
+        if i % steps_per_frame == 0 {
+            recorder.capture(width, height, |x, y| {
+                if point == (x as i32, y as i32).into() {
+                    GUARD
+                } else if input.obstructions.points[y][x] {
+                    OBSTRUCTION
+                } else if visited.points[y][x] {
+                    VISITED
+                } else {
+                    EMPTY
+                }
+            });
         }
     }
 
-    visited_points
+    recorder.capture(width, height, |x, y| {
+        if input.obstructions.points[y][x] {
+            OBSTRUCTION
+        } else if visited.points[y][x] {
+            VISITED
+        } else {
+            EMPTY
+        }
+    });
 }
 
 fn additional_obstruction_locations(input: &Map) -> usize {
@@ -1,4 +1,4 @@
-use aoc2024::get_input;
+use aoc2024::{get_input, CheckedSumExt};
 
 fn main() {
     let input = get_input(7);
@@ -72,7 +72,7 @@ fn num_solvable(input: &str, include_concat: bool) -> i64 {
                 None
             }
         })
-        .sum()
+        .checked_sum()
 }
 
 #[test]
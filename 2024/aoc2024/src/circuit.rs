@@ -0,0 +1,361 @@
+use std::{cell::OnceCell, fmt::Write};
+
+use crate::Graph;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum GateKind {
+    And,
+    Or,
+    Xor,
+}
+
+impl GateKind {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "AND" => Some(GateKind::And),
+            "OR" => Some(GateKind::Or),
+            "XOR" => Some(GateKind::Xor),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            GateKind::And => "AND",
+            GateKind::Or => "OR",
+            GateKind::Xor => "XOR",
+        }
+    }
+
+    pub fn apply(self, a: bool, b: bool) -> bool {
+        match self {
+            GateKind::And => a && b,
+            GateKind::Or => a || b,
+            GateKind::Xor => a ^ b,
+        }
+    }
+}
+
+/// A two input logic gate, referring to wires by index
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Gate {
+    pub kind: GateKind,
+    pub inputs: [usize; 2],
+    pub output: usize,
+}
+
+/// A network of logic gates connected by named wires, such as the one in
+/// 2024 day 24.
+///
+/// Each wire is driven by at most one gate, and wires which aren't driven by
+/// any gate are inputs. Groups of wires sharing a prefix, like `x00`, `x01`,
+/// ..., form a bus holding a number, with the lowest numbered wire as the
+/// least significant bit.
+#[derive(Clone, Debug)]
+pub struct Circuit {
+    /// An edge from each gate input to the gate's output
+    wires: Graph,
+    gates: Vec<Gate>,
+    drivers: Vec<Option<usize>>,
+    /// The wires in topological order, or a cycle if there is one. Calculated
+    /// the first time the circuit is evaluated.
+    order: OnceCell<Result<Vec<usize>, Vec<usize>>>,
+}
+
+impl Default for Circuit {
+    fn default() -> Self {
+        Self {
+            wires: Graph::new_directed(),
+            gates: vec![],
+            drivers: vec![],
+            order: OnceCell::new(),
+        }
+    }
+}
+
+impl Circuit {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parses lines like `x00 AND y00 -> z00`
+    pub fn parse(input: &str) -> Self {
+        let mut circuit = Self::new();
+
+        for line in input.lines().filter(|line| !line.is_empty()) {
+            let (gate, output) = line.split_once(" -> ").unwrap();
+            let [a, kind, b] = gate.split(' ').collect::<Vec<_>>()[..] else {
+                panic!("Invalid gate {line}");
+            };
+
+            let kind = GateKind::parse(kind).unwrap_or_else(|| panic!("Unknown gate {kind}"));
+            circuit.add_gate(kind, a, b, output);
+        }
+
+        circuit
+    }
+
+    pub fn wire(&mut self, name: &str) -> usize {
+        let index = self.wires.intern(name);
+        if index >= self.drivers.len() {
+            self.drivers.resize(index + 1, None);
+        }
+
+        index
+    }
+
+    pub fn wire_index(&self, name: &str) -> Option<usize> {
+        self.wires.index_of(name)
+    }
+
+    pub fn wire_name(&self, index: usize) -> &str {
+        self.wires.name(index)
+    }
+
+    pub fn wire_count(&self) -> usize {
+        self.wires.len()
+    }
+
+    pub fn gates(&self) -> &[Gate] {
+        &self.gates
+    }
+
+    /// The gate driving the given wire, if it isn't an input
+    pub fn driver(&self, wire: usize) -> Option<&Gate> {
+        self.drivers[wire].map(|gate| &self.gates[gate])
+    }
+
+    /// The gates which have the given wire as an input
+    pub fn consumers(&self, wire: usize) -> impl Iterator<Item = &Gate> + '_ {
+        self.wires
+            .neighbours(wire)
+            .filter_map(|output| self.driver(output))
+            .filter(move |gate| gate.inputs.contains(&wire))
+    }
+
+    pub fn add_gate(&mut self, kind: GateKind, a: &str, b: &str, output: &str) {
+        let inputs = [self.wire(a), self.wire(b)];
+        let output = self.wire(output);
+
+        assert!(
+            self.drivers[output].is_none(),
+            "Wire {} is driven by more than one gate",
+            self.wire_name(output)
+        );
+
+        for input in inputs {
+            self.wires.add_edge(input, output);
+        }
+
+        self.drivers[output] = Some(self.gates.len());
+        self.gates.push(Gate {
+            kind,
+            inputs,
+            output,
+        });
+        self.order = OnceCell::new();
+    }
+
+    /// Swaps which gates drive the two wires
+    pub fn swap_outputs(&mut self, a: usize, b: usize) {
+        let (Some(gate_a), Some(gate_b)) = (self.drivers[a], self.drivers[b]) else {
+            panic!("Can only swap wires which are driven by gates");
+        };
+
+        for (gate, output) in [(gate_a, a), (gate_b, b)] {
+            for input in self.gates[gate].inputs {
+                self.wires.remove_edge(input, output);
+            }
+        }
+
+        for (gate, output) in [(gate_a, b), (gate_b, a)] {
+            for input in self.gates[gate].inputs {
+                self.wires.add_edge(input, output);
+            }
+
+            self.gates[gate].output = output;
+            self.drivers[output] = Some(gate);
+        }
+
+        self.order = OnceCell::new();
+    }
+
+    /// The wires ordered so that every gate comes after both of its inputs, or
+    /// the wires making up a loop if there is one
+    pub fn topological_order(&self) -> Result<&[usize], &[usize]> {
+        self.order
+            .get_or_init(|| self.wires.topological_sort())
+            .as_deref()
+            .map_err(Vec::as_slice)
+    }
+
+    /// The wires whose names start with `prefix`, least significant bit first
+    pub fn bus(&self, prefix: &str) -> Vec<usize> {
+        let mut wires: Vec<_> = self
+            .wires
+            .nodes()
+            .filter(|&wire| self.wire_name(wire).starts_with(prefix))
+            .collect();
+
+        wires.sort_by(|&a, &b| self.wire_name(a).cmp(self.wire_name(b)));
+        wires
+    }
+
+    /// Works out the value of every wire in a single pass, given the values of
+    /// the inputs. Wires which depend on an input without a value are `None`.
+    ///
+    /// Returns `None` if the circuit contains a loop.
+    pub fn evaluate(&self, inputs: &[(usize, bool)]) -> Option<Vec<Option<bool>>> {
+        let order = self.topological_order().ok()?;
+
+        let mut values = vec![None; self.wire_count()];
+        for &(wire, value) in inputs {
+            values[wire] = Some(value);
+        }
+
+        for &wire in order {
+            if let Some(gate) = self.driver(wire) {
+                let [a, b] = gate.inputs.map(|input| values[input]);
+                values[wire] = a.zip(b).map(|(a, b)| gate.kind.apply(a, b));
+            }
+        }
+
+        Some(values)
+    }
+
+    /// Sets each bus to the given number, then reads the number on the output bus.
+    /// Returns `None` if the output isn't fully determined.
+    pub fn evaluate_buses(&self, inputs: &[(&str, u64)], output: &str) -> Option<u64> {
+        let inputs: Vec<_> = inputs
+            .iter()
+            .flat_map(|&(prefix, value)| {
+                self.bus(prefix)
+                    .into_iter()
+                    .enumerate()
+                    .map(move |(bit, wire)| (wire, value >> bit & 1 == 1))
+            })
+            .collect();
+
+        let values = self.evaluate(&inputs)?;
+        read_bus(&values, &self.bus(output))
+    }
+
+    /// The netlist in Graphviz DOT format, with a node for each wire labelled
+    /// by the gate driving it
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph circuit {\n    rankdir=LR;\n");
+
+        for wire in self.wires.nodes() {
+            let name = self.wire_name(wire);
+            match self.driver(wire) {
+                Some(gate) => writeln!(
+                    dot,
+                    "    \"{name}\" [label=\"{name}\\n{}\", shape=box];",
+                    gate.kind.name()
+                ),
+                None => writeln!(dot, "    \"{name}\" [shape=ellipse];"),
+            }
+            .unwrap();
+        }
+
+        for gate in &self.gates {
+            for input in gate.inputs {
+                writeln!(
+                    dot,
+                    "    \"{}\" -> \"{}\";",
+                    self.wire_name(input),
+                    self.wire_name(gate.output)
+                )
+                .unwrap();
+            }
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+/// Reads a number from the values of a bus, least significant bit first
+pub fn read_bus(values: &[Option<bool>], bus: &[usize]) -> Option<u64> {
+    bus.iter().rev().try_fold(0, |number, &wire| {
+        Some((number << 1) | u64::from(values[wire]?))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ADDER: &str = "x00 XOR y00 -> z00
+x00 AND y00 -> c01
+x01 XOR y01 -> s01
+s01 XOR c01 -> z01
+x01 AND y01 -> d01
+s01 AND c01 -> e01
+d01 OR e01 -> z02";
+
+    #[test]
+    fn evaluates_buses() {
+        let circuit = Circuit::parse(ADDER);
+
+        for x in 0..4 {
+            for y in 0..4 {
+                assert_eq!(
+                    circuit.evaluate_buses(&[("x", x), ("y", y)], "z"),
+                    Some(x + y)
+                );
+            }
+        }
+
+        assert_eq!(circuit.evaluate_buses(&[("x", 1)], "z"), None);
+
+        let z01 = circuit.wire_index("z01").unwrap();
+        assert_eq!(circuit.driver(z01).unwrap().kind, GateKind::Xor);
+        assert_eq!(
+            circuit
+                .consumers(circuit.wire_index("s01").unwrap())
+                .count(),
+            2
+        );
+    }
+
+    #[test]
+    fn swapping_outputs() {
+        let mut circuit = Circuit::parse(ADDER);
+        let (z01, e01) = (
+            circuit.wire_index("z01").unwrap(),
+            circuit.wire_index("e01").unwrap(),
+        );
+
+        circuit.swap_outputs(z01, e01);
+        assert_eq!(circuit.driver(z01).unwrap().kind, GateKind::And);
+        assert_eq!(circuit.evaluate_buses(&[("x", 2), ("y", 0)], "z"), Some(4));
+
+        circuit.swap_outputs(z01, e01);
+        assert_eq!(circuit.evaluate_buses(&[("x", 2), ("y", 0)], "z"), Some(2));
+
+        // swapping a gate's output with one of its inputs makes a loop
+        let s01 = circuit.wire_index("s01").unwrap();
+        circuit.swap_outputs(s01, z01);
+        assert!(circuit.topological_order().is_err());
+        assert_eq!(circuit.evaluate(&[]), None);
+    }
+
+    #[test]
+    fn dot_export() {
+        let circuit = Circuit::parse("a AND b -> c");
+
+        assert_eq!(
+            circuit.to_dot(),
+            "digraph circuit {
+    rankdir=LR;
+    \"a\" [shape=ellipse];
+    \"b\" [shape=ellipse];
+    \"c\" [label=\"c\\nAND\", shape=box];
+    \"a\" -> \"c\";
+    \"b\" -> \"c\";
+}
+"
+        );
+    }
+}
@@ -0,0 +1,316 @@
+use std::{cmp::Reverse, collections::BinaryHeap, fmt};
+
+/// A run of `len` blocks on a disk, starting at block `start`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Extent {
+    pub start: usize,
+    pub len: usize,
+}
+
+impl Extent {
+    pub fn end(&self) -> usize {
+        self.start + self.len
+    }
+}
+
+/// How [`DiskMap::compact`] moves files towards the start of the disk
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Compaction {
+    /// Moves blocks one at a time from the end of the disk into the first free
+    /// block, which leaves no gaps but splits files up
+    Blocks,
+    /// Moves each file in one piece to the first gap big enough for it, trying
+    /// each file once in order of decreasing id
+    WholeFiles,
+}
+
+/// The files on a disk, like the dense disk map from 2024 day 9.
+///
+/// Files are identified by their index, and each can be split into several
+/// extents. Any block which isn't part of a file is free.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DiskMap {
+    files: Vec<Vec<Extent>>,
+    size: usize,
+}
+
+impl DiskMap {
+    /// Parses a dense disk map, where the digits alternate between the length
+    /// of a file and the length of the free space after it
+    pub fn parse(input: &str) -> Self {
+        let mut files = vec![];
+        let mut position = 0;
+
+        for (i, c) in input.trim().chars().enumerate() {
+            let len = c.to_digit(10).unwrap() as usize;
+
+            if i % 2 == 0 {
+                files.push(vec![Extent {
+                    start: position,
+                    len,
+                }]);
+            }
+
+            position += len;
+        }
+
+        Self {
+            files,
+            size: position,
+        }
+    }
+
+    /// The number of blocks on the disk
+    pub fn len(&self) -> usize {
+        self.size
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.size == 0
+    }
+
+    pub fn file_count(&self) -> usize {
+        self.files.len()
+    }
+
+    /// Where the file with the given id is, in order along the disk
+    pub fn extents(&self, id: usize) -> &[Extent] {
+        &self.files[id]
+    }
+
+    /// The runs of free blocks, in order along the disk
+    pub fn free_extents(&self) -> Vec<Extent> {
+        let mut used: Vec<Extent> = self.files.iter().flatten().copied().collect();
+        used.sort();
+
+        let mut free = vec![];
+        let mut position = 0;
+        for extent in used.into_iter().chain([Extent {
+            start: self.size,
+            len: 0,
+        }]) {
+            if extent.start > position {
+                free.push(Extent {
+                    start: position,
+                    len: extent.start - position,
+                });
+            }
+
+            position = position.max(extent.end());
+        }
+
+        free
+    }
+
+    pub fn compact(&mut self, strategy: Compaction) {
+        match strategy {
+            Compaction::Blocks => self.compact_blocks(),
+            Compaction::WholeFiles => self.compact_whole_files(),
+        }
+    }
+
+    fn compact_blocks(&mut self) {
+        let mut pieces: Vec<(Extent, usize)> = self
+            .files
+            .iter()
+            .enumerate()
+            .flat_map(|(id, extents)| extents.iter().map(move |&extent| (extent, id)))
+            .collect();
+        pieces.sort();
+
+        let free = self.free_extents();
+        let mut free = free.into_iter().filter(|extent| extent.len > 0).peekable();
+
+        // take blocks from the end of the last piece and put them in the first
+        // free extent, until the free space is all after the files
+        let mut compacted: Vec<(Extent, usize)> = vec![];
+        while let Some((mut piece, id)) = pieces.pop() {
+            while piece.len > 0 {
+                let Some(gap) = free.peek_mut().filter(|gap| gap.start < piece.start) else {
+                    break;
+                };
+
+                let moved = gap.len.min(piece.len);
+                compacted.push((
+                    Extent {
+                        start: gap.start,
+                        len: moved,
+                    },
+                    id,
+                ));
+
+                piece.len -= moved;
+                gap.start += moved;
+                gap.len -= moved;
+
+                if gap.len == 0 {
+                    free.next();
+                }
+            }
+
+            if piece.len > 0 {
+                compacted.push((piece, id));
+            }
+        }
+
+        for extents in &mut self.files {
+            extents.clear();
+        }
+
+        compacted.sort();
+        for (extent, id) in compacted {
+            let extents = &mut self.files[id];
+            match extents.last_mut() {
+                Some(last) if last.end() == extent.start => last.len += extent.len,
+                _ => extents.push(extent),
+            }
+        }
+    }
+
+    fn compact_whole_files(&mut self) {
+        // a heap of the starts of the free extents of each length, so the
+        // leftmost gap which fits a file is the smallest start across the
+        // heaps for lengths at least as big as the file
+        let free = self.free_extents();
+        let longest = free.iter().map(|extent| extent.len).max().unwrap_or(0);
+
+        let mut gaps = vec![BinaryHeap::new(); longest + 1];
+        for extent in free {
+            gaps[extent.len].push(Reverse(extent.start));
+        }
+
+        for id in (0..self.files.len()).rev() {
+            let len: usize = self.files[id].iter().map(|extent| extent.len).sum();
+            let Some(start) = self.files[id].first().map(|extent| extent.start) else {
+                continue;
+            };
+
+            if len == 0 || len > longest {
+                continue;
+            }
+
+            let best = (len..=longest)
+                .filter_map(|gap_len| gaps[gap_len].peek().map(|&Reverse(gap)| (gap, gap_len)))
+                .min();
+
+            let Some((gap, gap_len)) = best.filter(|&(gap, _)| gap < start) else {
+                continue;
+            };
+
+            gaps[gap_len].pop();
+            if gap_len > len {
+                gaps[gap_len - len].push(Reverse(gap + len));
+            }
+
+            // the space the file leaves behind is after every file still to be
+            // moved, so it doesn't need to go back in the heaps
+            self.files[id] = vec![Extent { start: gap, len }];
+        }
+    }
+
+    /// The sum of each block's position multiplied by the id of the file in it
+    pub fn checksum(&self) -> usize {
+        self.files
+            .iter()
+            .enumerate()
+            .flat_map(|(id, extents)| {
+                extents.iter().map(move |extent| {
+                    // the sum of start..end
+                    id * (extent.len * extent.start + extent.len * extent.len.saturating_sub(1) / 2)
+                })
+            })
+            .sum()
+    }
+
+    /// The id of the file in each block, or `None` for free blocks
+    pub fn blocks(&self) -> Vec<Option<usize>> {
+        let mut blocks = vec![None; self.size];
+
+        for (id, extents) in self.files.iter().enumerate() {
+            for extent in extents {
+                blocks[extent.start..extent.end()].fill(Some(id));
+            }
+        }
+
+        blocks
+    }
+}
+
+/// Shows each block as the id of the file in it, or `.` if it's free
+impl fmt::Display for DiskMap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for block in self.blocks() {
+            match block {
+                Some(id) => write!(f, "{id}")?,
+                None => write!(f, ".")?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "2333133121414131402";
+
+    #[test]
+    fn parse_and_render() {
+        let disk = DiskMap::parse(EXAMPLE);
+
+        assert_eq!(
+            disk.to_string(),
+            "00...111...2...333.44.5555.6666.777.888899"
+        );
+        assert_eq!(disk.file_count(), 10);
+        assert_eq!(disk.free_extents()[0], Extent { start: 2, len: 3 });
+    }
+
+    #[test]
+    fn block_compaction() {
+        let mut disk = DiskMap::parse(EXAMPLE);
+        disk.compact(Compaction::Blocks);
+
+        assert_eq!(
+            disk.to_string(),
+            "0099811188827773336446555566.............."
+        );
+        assert_eq!(disk.checksum(), 1928);
+        assert_eq!(
+            disk.extents(8),
+            &[Extent { start: 4, len: 1 }, Extent { start: 8, len: 3 }]
+        );
+        assert_eq!(disk.free_extents(), vec![Extent { start: 28, len: 14 }]);
+    }
+
+    #[test]
+    fn whole_file_compaction() {
+        let mut disk = DiskMap::parse(EXAMPLE);
+        disk.compact(Compaction::WholeFiles);
+
+        assert_eq!(
+            disk.to_string(),
+            "00992111777.44.333....5555.6666.....8888.."
+        );
+        assert_eq!(disk.checksum(), 2858);
+    }
+
+    #[test]
+    fn checksum_matches_blocks() {
+        let mut disk = DiskMap::parse("12345678901357924680");
+
+        for strategy in [Compaction::WholeFiles, Compaction::Blocks] {
+            disk.compact(strategy);
+
+            let expected: usize = disk
+                .blocks()
+                .iter()
+                .enumerate()
+                .map(|(i, block)| i * block.unwrap_or(0))
+                .sum();
+            assert_eq!(disk.checksum(), expected);
+        }
+    }
+}
@@ -0,0 +1,294 @@
+use std::collections::VecDeque;
+
+use crate::{Direction, Grid2, Vector2D};
+
+/// Something which can be pushed around, covering the cells at `position`
+/// plus each offset in `shape`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PushObject {
+    pub position: Vector2D<i32>,
+    pub shape: Vec<Vector2D<i32>>,
+}
+
+impl PushObject {
+    pub fn cells(&self) -> impl Iterator<Item = Vector2D<i32>> + '_ {
+        self.shape.iter().map(|&offset| self.position + offset)
+    }
+}
+
+/// The result of a single call to [`PushWorld::push`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PushStep {
+    pub direction: Direction,
+    /// The objects which moved, starting with the one doing the pushing. Empty
+    /// if the push was blocked by a wall.
+    pub moved: Vec<usize>,
+}
+
+/// A grid of walls with objects of any shape in it, where pushing an object
+/// also pushes everything in its way, like the robot and boxes in 2024 day 15.
+///
+/// A push only happens if nothing along the way would end up in a wall, in
+/// which case nothing moves at all. Every push is remembered so it can be undone.
+#[derive(Clone, Debug)]
+pub struct PushWorld {
+    walls: Grid2<bool>,
+    occupied: Grid2<Option<usize>>,
+    objects: Vec<PushObject>,
+    history: Vec<PushStep>,
+}
+
+impl PushWorld {
+    pub fn new(walls: Grid2<bool>) -> Self {
+        let occupied = Grid2::new(walls.width, walls.height);
+
+        Self {
+            walls,
+            occupied,
+            objects: vec![],
+            history: vec![],
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.walls.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.walls.height
+    }
+
+    /// Anything outside the grid counts as a wall
+    pub fn is_wall(&self, point: Vector2D<i32>) -> bool {
+        *self.walls.get::<i32>(point).unwrap_or(&true)
+    }
+
+    pub fn object_at(&self, point: Vector2D<i32>) -> Option<usize> {
+        self.occupied.get::<i32>(point).copied().flatten()
+    }
+
+    pub fn object(&self, id: usize) -> &PushObject {
+        &self.objects[id]
+    }
+
+    pub fn objects(&self) -> &[PushObject] {
+        &self.objects
+    }
+
+    pub fn history(&self) -> &[PushStep] {
+        &self.history
+    }
+
+    /// Adds an object and returns its id. Panics if it would overlap a wall or
+    /// another object.
+    pub fn add_object(&mut self, position: Vector2D<i32>, shape: Vec<Vector2D<i32>>) -> usize {
+        let id = self.objects.len();
+        self.objects.push(PushObject { position, shape });
+
+        for cell in self.objects[id].cells() {
+            assert!(!self.is_wall(cell), "Can't put an object in a wall");
+            let previous = self.occupied.set::<i32>(cell, Some(id)).unwrap();
+            assert!(previous.is_none(), "Objects can't overlap");
+        }
+
+        id
+    }
+
+    /// Tries to move the object `id` one step in `direction`, pushing anything
+    /// in the way along with it
+    pub fn push(&mut self, id: usize, direction: Direction) -> &PushStep {
+        let moved = self.pushed_objects(id, direction).unwrap_or_default();
+        self.shift(&moved, direction.into());
+
+        self.check_moved(&moved);
+        self.history.push(PushStep { direction, moved });
+
+        self.history.last().unwrap()
+    }
+
+    /// Reverses the most recent push
+    pub fn undo(&mut self) -> Option<PushStep> {
+        let step = self.history.pop()?;
+
+        let back: Vector2D<i32> = step.direction.into();
+        self.shift(&step.moved, -back);
+        self.check_moved(&step.moved);
+
+        Some(step)
+    }
+
+    /// Every object which would move if `id` was pushed, or `None` if one of
+    /// them would hit a wall
+    fn pushed_objects(&self, id: usize, direction: Direction) -> Option<Vec<usize>> {
+        let step: Vector2D<i32> = direction.into();
+
+        let mut moving = vec![id];
+        let mut queue = VecDeque::from([id]);
+
+        while let Some(current) = queue.pop_front() {
+            for cell in self.objects[current].cells() {
+                let target = cell + step;
+                if self.is_wall(target) {
+                    return None;
+                }
+
+                if let Some(other) = self.object_at(target) {
+                    if !moving.contains(&other) {
+                        moving.push(other);
+                        queue.push_back(other);
+                    }
+                }
+            }
+        }
+
+        Some(moving)
+    }
+
+    fn shift(&mut self, ids: &[usize], by: Vector2D<i32>) {
+        // clear everything first so that objects can move into each other's
+        // old cells
+        for &id in ids {
+            for cell in self.objects[id].cells() {
+                self.occupied.set::<i32>(cell, None);
+            }
+        }
+
+        for &id in ids {
+            self.objects[id].position += by;
+            for cell in self.objects[id].cells() {
+                self.occupied.set::<i32>(cell, Some(id));
+            }
+        }
+    }
+
+    /// Checks that the objects which just moved aren't in a wall and own every
+    /// cell they cover, in debug builds. Only the moved objects are looked at
+    /// so that this stays cheap, as a push never touches any other cells.
+    fn check_moved(&self, ids: &[usize]) {
+        if !cfg!(debug_assertions) {
+            return;
+        }
+
+        for &id in ids {
+            for cell in self.objects[id].cells() {
+                assert!(!self.is_wall(cell), "Object {id} is in a wall at {cell:?}");
+                assert_eq!(
+                    self.object_at(cell),
+                    Some(id),
+                    "Object {id} overlaps another at {cell:?}"
+                );
+            }
+        }
+    }
+
+    /// Checks every cell of the grid against every object. This is too slow
+    /// to run after each push, so it's only used by the tests.
+    #[cfg(test)]
+    fn check_invariants(&self) {
+        let mut seen = Grid2::new(self.width(), self.height());
+        for (id, object) in self.objects.iter().enumerate() {
+            for cell in object.cells() {
+                assert!(!self.is_wall(cell), "Object {id} is in a wall at {cell:?}");
+                assert_eq!(
+                    seen.set::<i32>(cell, Some(id)),
+                    Some(None),
+                    "Object {id} overlaps another at {cell:?}"
+                );
+            }
+        }
+
+        assert_eq!(
+            seen, self.occupied,
+            "Occupied cells don't match the objects"
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A 6x3 room with walls around the outside
+    fn room() -> PushWorld {
+        let walls = Grid2::parse("######\n#....#\n######", |line| {
+            line.chars().map(|c| c == '#').collect()
+        });
+
+        PushWorld::new(walls)
+    }
+
+    #[test]
+    fn pushes_chains_of_objects() {
+        let mut world = room();
+        let pusher = world.add_object((1, 1).into(), vec![(0, 0).into()]);
+        let wide = world.add_object((2, 1).into(), vec![(0, 0).into(), (1, 0).into()]);
+
+        assert_eq!(
+            world.push(pusher, Direction::East).moved,
+            vec![pusher, wide]
+        );
+        assert_eq!(world.object(wide).position, (3, 1).into());
+        assert_eq!(world.object_at((4, 1).into()), Some(wide));
+        assert_eq!(world.object_at((1, 1).into()), None);
+
+        // the wide object is now against the wall, so nothing moves
+        assert!(world.push(pusher, Direction::East).moved.is_empty());
+        assert_eq!(world.object(pusher).position, (2, 1).into());
+
+        assert!(world.push(pusher, Direction::North).moved.is_empty());
+        world.check_invariants();
+    }
+
+    #[test]
+    fn pushes_spread_out_sideways() {
+        // a wide box sitting on top of two small ones
+        let walls = Grid2::new_with(5, 5, || false);
+        let mut world = PushWorld::new(walls);
+
+        let pusher = world.add_object((1, 4).into(), vec![(0, 0).into()]);
+        let wide = world.add_object((1, 3).into(), vec![(0, 0).into(), (1, 0).into()]);
+        let left = world.add_object((1, 2).into(), vec![(0, 0).into()]);
+        let right = world.add_object((2, 2).into(), vec![(0, 0).into()]);
+
+        let moved = &world.push(pusher, Direction::North).moved;
+        assert_eq!(moved, &vec![pusher, wide, left, right]);
+        assert_eq!(world.object(right).position, (2, 1).into());
+        world.check_invariants();
+    }
+
+    #[test]
+    fn undo() {
+        let mut world = room();
+        let pusher = world.add_object((1, 1).into(), vec![(0, 0).into()]);
+        let other = world.add_object((3, 1).into(), vec![(0, 0).into()]);
+
+        for _ in 0..4 {
+            world.push(pusher, Direction::East);
+        }
+        assert_eq!(world.object(other).position, (4, 1).into());
+        assert_eq!(world.history().len(), 4);
+        world.check_invariants();
+
+        // undoing the blocked pushes doesn't move anything
+        world.undo();
+        world.undo();
+        assert_eq!(world.object(pusher).position, (3, 1).into());
+
+        let step = world.undo().unwrap();
+        assert_eq!(step.moved, vec![pusher, other]);
+        assert_eq!(world.object(other).position, (3, 1).into());
+
+        world.undo();
+        assert_eq!(world.object(pusher).position, (1, 1).into());
+        assert_eq!(world.undo(), None);
+        world.check_invariants();
+    }
+
+    #[test]
+    #[should_panic = "Objects can't overlap"]
+    fn rejects_overlaps() {
+        let mut world = room();
+        world.add_object((1, 1).into(), vec![(0, 0).into(), (1, 0).into()]);
+        world.add_object((2, 1).into(), vec![(0, 0).into()]);
+    }
+}
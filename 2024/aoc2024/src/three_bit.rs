@@ -0,0 +1,1068 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    fmt::{self, Display},
+    sync::LazyLock,
+};
+
+use anyhow::{bail, Context};
+use regex::Regex;
+
+/// The instructions of the 3-bit computer from 2024 day 17
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, enumn::N)]
+pub enum Opcode {
+    Adv,
+    Bxl,
+    Bst,
+    Jnz,
+    Bxc,
+    Out,
+    Bdv,
+    Cdv,
+}
+
+impl Opcode {
+    const ALL: [Opcode; 8] = [
+        Opcode::Adv,
+        Opcode::Bxl,
+        Opcode::Bst,
+        Opcode::Jnz,
+        Opcode::Bxc,
+        Opcode::Out,
+        Opcode::Bdv,
+        Opcode::Cdv,
+    ];
+
+    pub fn mnemonic(self) -> &'static str {
+        match self {
+            Opcode::Adv => "adv",
+            Opcode::Bxl => "bxl",
+            Opcode::Bst => "bst",
+            Opcode::Jnz => "jnz",
+            Opcode::Bxc => "bxc",
+            Opcode::Out => "out",
+            Opcode::Bdv => "bdv",
+            Opcode::Cdv => "cdv",
+        }
+    }
+
+    pub fn from_mnemonic(mnemonic: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|opcode| opcode.mnemonic().eq_ignore_ascii_case(mnemonic))
+    }
+
+    /// Whether the operand is a combo operand rather than a literal
+    pub fn takes_combo(self) -> bool {
+        matches!(
+            self,
+            Opcode::Adv | Opcode::Bst | Opcode::Out | Opcode::Bdv | Opcode::Cdv
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ComboOperand {
+    Literal(usize),
+    A,
+    B,
+    C,
+    /// Combo operand 7, which doesn't appear in valid programs
+    Reserved,
+}
+
+impl ComboOperand {
+    pub fn from_int(input: usize) -> Self {
+        match input {
+            0..=3 => ComboOperand::Literal(input),
+            4 => ComboOperand::A,
+            5 => ComboOperand::B,
+            6 => ComboOperand::C,
+            7 => ComboOperand::Reserved,
+            _ => panic!("Unknown combo operand {input}"),
+        }
+    }
+}
+
+impl Display for ComboOperand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ComboOperand::Literal(value) => write!(f, "{value}"),
+            ComboOperand::A => write!(f, "a"),
+            ComboOperand::B => write!(f, "b"),
+            ComboOperand::C => write!(f, "c"),
+            ComboOperand::Reserved => write!(f, "reserved"),
+        }
+    }
+}
+
+/// A decoded instruction along with its raw operand
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ThreeBitInstruction {
+    pub opcode: Opcode,
+    pub operand: usize,
+}
+
+impl ThreeBitInstruction {
+    pub fn decode(opcode: usize, operand: usize) -> Option<Self> {
+        Some(Self {
+            opcode: Opcode::n(u8::try_from(opcode).ok()?)?,
+            operand: (operand < 8).then_some(operand)?,
+        })
+    }
+
+    pub fn combo(self) -> ComboOperand {
+        ComboOperand::from_int(self.operand)
+    }
+
+    fn write_with_label(&self, f: &mut impl fmt::Write, label: Option<&str>) -> fmt::Result {
+        write!(f, "{}", self.opcode.mnemonic())?;
+
+        match self.opcode {
+            _ if self.opcode.takes_combo() => write!(f, " {}", self.combo()),
+            // bxc ignores its operand, so only show it if it isn't the usual 0
+            Opcode::Bxc if self.operand == 0 => Ok(()),
+            Opcode::Jnz => match label {
+                Some(label) => write!(f, " {label}"),
+                None => write!(f, " {}", self.operand),
+            },
+            _ => write!(f, " {}", self.operand),
+        }
+    }
+}
+
+impl Display for ThreeBitInstruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write_with_label(f, None)
+    }
+}
+
+/// What happened when executing a single instruction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StepResult {
+    Continue,
+    Output(usize),
+    Halted,
+}
+
+/// Why [`ThreeBitComputer::resume`] stopped
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StopReason {
+    Breakpoint(usize),
+    Halted,
+}
+
+/// The machine state after executing one instruction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TraceEntry {
+    pub pc: usize,
+    pub instruction: ThreeBitInstruction,
+    pub a: usize,
+    pub b: usize,
+    pub c: usize,
+    pub output: Option<usize>,
+}
+
+/// The 3-bit computer from 2024 day 17
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ThreeBitComputer {
+    pub a: usize,
+    pub b: usize,
+    pub c: usize,
+    pub pc: usize,
+    pub program: Vec<usize>,
+    /// Addresses which [`ThreeBitComputer::resume`] will stop before executing
+    pub breakpoints: BTreeSet<usize>,
+}
+
+impl ThreeBitComputer {
+    pub fn new(program: Vec<usize>, a: usize, b: usize, c: usize) -> Self {
+        Self {
+            a,
+            b,
+            c,
+            pc: 0,
+            program,
+            breakpoints: BTreeSet::new(),
+        }
+    }
+
+    pub fn parse(input: &str) -> Self {
+        static PROGRAM_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+            Regex::new(r"Register A: ([0-9]+)\nRegister B: ([0-9]+)\nRegister C: ([0-9]+)\n\nProgram: (.*)").unwrap()
+        });
+
+        let (_, [a, b, c, program]) = PROGRAM_REGEX.captures(input).unwrap().extract();
+
+        let program = program.split(',').map(|i| i.parse().unwrap()).collect();
+
+        Self::new(
+            program,
+            a.parse().unwrap(),
+            b.parse().unwrap(),
+            c.parse().unwrap(),
+        )
+    }
+
+    /// Puts the machine back at the start of the program with the given registers
+    pub fn reset(&mut self, a: usize, b: usize, c: usize) {
+        self.a = a;
+        self.b = b;
+        self.c = c;
+        self.pc = 0;
+    }
+
+    pub fn is_halted(&self) -> bool {
+        self.pc + 1 >= self.program.len()
+    }
+
+    /// The instruction which will be executed next
+    pub fn current_instruction(&self) -> Option<ThreeBitInstruction> {
+        if self.is_halted() {
+            return None;
+        }
+
+        let instruction =
+            ThreeBitInstruction::decode(self.program[self.pc], self.program[self.pc + 1]);
+        Some(instruction.unwrap_or_else(|| panic!("Invalid instruction at {}", self.pc)))
+    }
+
+    fn get_combo(&self, operand: ComboOperand) -> usize {
+        match operand {
+            ComboOperand::Literal(v) => v,
+            ComboOperand::A => self.a,
+            ComboOperand::B => self.b,
+            ComboOperand::C => self.c,
+            ComboOperand::Reserved => panic!("Reserved combo operand at {}", self.pc),
+        }
+    }
+
+    pub fn step(&mut self) -> StepResult {
+        let Some(instruction) = self.current_instruction() else {
+            return StepResult::Halted;
+        };
+
+        let mut result = StepResult::Continue;
+
+        match instruction.opcode {
+            Opcode::Adv => self.a >>= self.get_combo(instruction.combo()),
+            Opcode::Bxl => self.b ^= instruction.operand,
+            Opcode::Bst => self.b = self.get_combo(instruction.combo()) % 8,
+            Opcode::Jnz => {
+                if self.a != 0 {
+                    self.pc = instruction.operand;
+                    return result;
+                }
+            }
+            Opcode::Bxc => self.b ^= self.c,
+            Opcode::Out => result = StepResult::Output(self.get_combo(instruction.combo()) % 8),
+            Opcode::Bdv => self.b = self.a >> self.get_combo(instruction.combo()),
+            Opcode::Cdv => self.c = self.a >> self.get_combo(instruction.combo()),
+        }
+
+        self.pc += 2;
+        result
+    }
+
+    /// Runs until the next output, returning `None` if the program halts first
+    pub fn until_out(&mut self) -> Option<usize> {
+        loop {
+            match self.step() {
+                StepResult::Continue => {}
+                StepResult::Output(value) => return Some(value),
+                StepResult::Halted => return None,
+            }
+        }
+    }
+
+    /// Runs until the program halts, returning everything it outputs
+    pub fn run(&mut self) -> Vec<usize> {
+        std::iter::from_fn(|| self.until_out()).collect()
+    }
+
+    /// Runs until the program halts, returning the output separated by commas
+    pub fn until_halt(&mut self) -> String {
+        self.run()
+            .iter()
+            .map(|value| value.to_string())
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+
+    /// Runs until just before an instruction with a breakpoint, or until the
+    /// program halts, pushing anything output along the way into `output`.
+    ///
+    /// Always executes at least one instruction, so calling this again after
+    /// stopping at a breakpoint carries on past it.
+    pub fn resume(&mut self, output: &mut Vec<usize>) -> StopReason {
+        loop {
+            match self.step() {
+                StepResult::Continue => {}
+                StepResult::Output(value) => output.push(value),
+                StepResult::Halted => return StopReason::Halted,
+            }
+
+            if self.breakpoints.contains(&self.pc) && !self.is_halted() {
+                return StopReason::Breakpoint(self.pc);
+            }
+        }
+    }
+
+    /// Runs for at most `max_steps` instructions, recording the state after each one
+    pub fn trace(&mut self, max_steps: usize) -> Vec<TraceEntry> {
+        let mut trace = vec![];
+
+        for _ in 0..max_steps {
+            let pc = self.pc;
+            let Some(instruction) = self.current_instruction() else {
+                break;
+            };
+
+            let output = match self.step() {
+                StepResult::Output(value) => Some(value),
+                _ => None,
+            };
+
+            trace.push(TraceEntry {
+                pc,
+                instruction,
+                a: self.a,
+                b: self.b,
+                c: self.c,
+                output,
+            });
+        }
+
+        trace
+    }
+
+    /// Turns assembly into a program. Each line is either an instruction such
+    /// as `adv 3`, `bst a` or `jnz loop`, or a label such as `loop:`. Anything
+    /// after a `;` is a comment.
+    pub fn assemble(source: &str) -> anyhow::Result<Vec<usize>> {
+        let mut labels = HashMap::new();
+        let mut instructions = vec![];
+
+        for (line_number, line) in source.lines().enumerate() {
+            let line = line.split(';').next().unwrap().trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            if let Some(label) = line.strip_suffix(':') {
+                if labels
+                    .insert(label.trim().to_string(), instructions.len() * 2)
+                    .is_some()
+                {
+                    bail!("Line {}: duplicate label {label}", line_number + 1);
+                }
+
+                continue;
+            }
+
+            let mut parts = line.split_whitespace();
+            let mnemonic = parts.next().unwrap();
+            let opcode = Opcode::from_mnemonic(mnemonic).with_context(|| {
+                format!("Line {}: unknown instruction {mnemonic}", line_number + 1)
+            })?;
+            let operand = parts.next();
+
+            if let Some(extra) = parts.next() {
+                bail!("Line {}: unexpected {extra}", line_number + 1);
+            }
+
+            instructions.push((line_number + 1, opcode, operand));
+        }
+
+        let mut program = vec![];
+        for (line_number, opcode, operand) in instructions {
+            let operand = match (opcode, operand) {
+                (Opcode::Bxc, None) => 0,
+                (_, None) => bail!("Line {line_number}: {} needs an operand", opcode.mnemonic()),
+                (_, Some(operand)) if opcode.takes_combo() => match operand {
+                    "a" | "A" => 4,
+                    "b" | "B" => 5,
+                    "c" | "C" => 6,
+                    _ => match operand.parse() {
+                        Ok(value @ 0..=3) => value,
+                        _ => bail!("Line {line_number}: invalid combo operand {operand}"),
+                    },
+                },
+                (Opcode::Jnz, Some(operand)) if labels.contains_key(operand) => labels[operand],
+                (_, Some(operand)) => match operand.parse() {
+                    Ok(value @ 0..=7) => value,
+                    _ => bail!("Line {line_number}: invalid literal operand {operand}"),
+                },
+            };
+
+            program.extend([opcode as usize, operand]);
+        }
+
+        Ok(program)
+    }
+
+    /// Turns a program back into assembly which [`ThreeBitComputer::assemble`]
+    /// accepts, adding labels for the targets of jumps
+    pub fn disassemble(program: &[usize]) -> Listing {
+        let instructions: Vec<_> = program
+            .chunks_exact(2)
+            .map(|chunk| {
+                ThreeBitInstruction::decode(chunk[0], chunk[1])
+                    .unwrap_or_else(|| panic!("Invalid instruction {chunk:?}"))
+            })
+            .collect();
+
+        let targets: BTreeSet<_> = instructions
+            .iter()
+            .filter(|instruction| instruction.opcode == Opcode::Jnz)
+            .map(|instruction| instruction.operand)
+            .filter(|&target| target % 2 == 0 && target < program.len())
+            .collect();
+
+        let labels = targets
+            .into_iter()
+            .enumerate()
+            .map(|(i, target)| (target, format!("L{i}")))
+            .collect();
+
+        Listing {
+            instructions,
+            labels,
+        }
+    }
+}
+
+/// A disassembled program
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Listing {
+    pub instructions: Vec<ThreeBitInstruction>,
+    /// The label for each address which is jumped to
+    pub labels: BTreeMap<usize, String>,
+}
+
+impl Listing {
+    /// The label at the given address, if anything jumps there
+    pub fn label_at(&self, address: usize) -> Option<&str> {
+        self.labels.get(&address).map(String::as_str)
+    }
+}
+
+impl Display for Listing {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, instruction) in self.instructions.iter().enumerate() {
+            if let Some(label) = self.label_at(i * 2) {
+                writeln!(f, "{label}:")?;
+            }
+
+            write!(f, "    ")?;
+            let target = (instruction.opcode == Opcode::Jnz)
+                .then(|| self.label_at(instruction.operand))
+                .flatten();
+            instruction.write_with_label(f, target)?;
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Why [`ThreeBitComputer::find_quine`] couldn't find a quine
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuineError {
+    /// The only jump must be a `jnz 0` at the very end, so the program is one loop
+    NotASingleLoop,
+    /// Register a must be shifted right by the same non-zero literal amount
+    /// every time round the loop
+    VariableShift,
+    /// Each time round the loop must output exactly one value
+    OutputsPerIteration(usize),
+    /// The register is read before it's written in the loop, so the output
+    /// depends on the previous iteration rather than just on a
+    CarriedRegister(char),
+    /// The program has the right shape, but no starting value of a works
+    NoSolution,
+}
+
+impl Display for QuineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QuineError::NotASingleLoop => write!(f, "program isn't a single loop ending in jnz 0"),
+            QuineError::VariableShift => {
+                write!(f, "a isn't shifted by a constant amount each iteration")
+            }
+            QuineError::OutputsPerIteration(count) => {
+                write!(f, "loop outputs {count} values per iteration rather than 1")
+            }
+            QuineError::CarriedRegister(register) => {
+                write!(f, "register {register} is carried between iterations")
+            }
+            QuineError::NoSolution => write!(f, "no value of a makes the program output itself"),
+        }
+    }
+}
+
+impl std::error::Error for QuineError {}
+
+impl ThreeBitComputer {
+    /// Checks that the program is a loop which, each time round, outputs one
+    /// value depending only on the current value of a and then shifts a right
+    /// by a constant amount. Returns that amount.
+    pub fn loop_shift(&self) -> Result<usize, QuineError> {
+        let listing = Self::disassemble(&self.program);
+        let Some((last, body)) = listing.instructions.split_last() else {
+            return Err(QuineError::NotASingleLoop);
+        };
+
+        if *last
+            != (ThreeBitInstruction {
+                opcode: Opcode::Jnz,
+                operand: 0,
+            })
+            || !self.program.len().is_multiple_of(2)
+        {
+            return Err(QuineError::NotASingleLoop);
+        }
+
+        let mut shift = 0;
+        let mut outputs = 0;
+        let (mut b_written, mut c_written) = (false, false);
+
+        for instruction in body {
+            let mut reads = vec![];
+            if instruction.opcode.takes_combo() {
+                reads.push(instruction.combo());
+            }
+
+            match instruction.opcode {
+                Opcode::Bxl => reads.push(ComboOperand::B),
+                Opcode::Bxc => reads.extend([ComboOperand::B, ComboOperand::C]),
+                _ => {}
+            }
+
+            for read in reads {
+                match read {
+                    ComboOperand::B if !b_written => return Err(QuineError::CarriedRegister('b')),
+                    ComboOperand::C if !c_written => return Err(QuineError::CarriedRegister('c')),
+                    _ => {}
+                }
+            }
+
+            match instruction.opcode {
+                Opcode::Adv => match instruction.combo() {
+                    ComboOperand::Literal(amount) => shift += amount,
+                    _ => return Err(QuineError::VariableShift),
+                },
+                Opcode::Bxl | Opcode::Bst | Opcode::Bxc | Opcode::Bdv => b_written = true,
+                Opcode::Cdv => c_written = true,
+                Opcode::Out => outputs += 1,
+                Opcode::Jnz => return Err(QuineError::NotASingleLoop),
+            }
+        }
+
+        if outputs != 1 {
+            return Err(QuineError::OutputsPerIteration(outputs));
+        }
+
+        if shift == 0 {
+            return Err(QuineError::VariableShift);
+        }
+
+        Ok(shift)
+    }
+
+    /// Finds the smallest starting value of register a which makes the program
+    /// output a copy of itself, for programs accepted by
+    /// [`ThreeBitComputer::loop_shift`].
+    ///
+    /// Each iteration sees a shifted right by one more chunk, so the last
+    /// output only depends on the top chunk of a. This builds up a from the
+    /// top, one chunk per output, backtracking when no chunk works.
+    pub fn find_quine(&self) -> Result<usize, QuineError> {
+        let shift = self.loop_shift()?;
+        let mut computer = self.clone();
+
+        computer
+            .quine_from(shift, &self.program, 0)
+            .ok_or(QuineError::NoSolution)
+    }
+
+    fn quine_from(
+        &mut self,
+        shift: usize,
+        remaining_output: &[usize],
+        current_a: usize,
+    ) -> Option<usize> {
+        let Some((&target_value, remaining)) = remaining_output.split_last() else {
+            return Some(current_a);
+        };
+
+        for chunk in 0..1 << shift {
+            let next_a = (current_a << shift) | chunk;
+            if next_a == 0 {
+                // a = 0 would stop the loop an iteration early
+                continue;
+            }
+
+            let (b, c) = (self.b, self.c);
+            self.reset(next_a, b, c);
+            let output = self.until_out();
+            self.reset(current_a, b, c);
+
+            if output == Some(target_value) {
+                if let Some(quine) = self.quine_from(shift, remaining, next_a) {
+                    return Some(quine);
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Tries every starting value of a below `limit` by running the whole
+    /// program, for programs where [`ThreeBitComputer::find_quine`] can't be used
+    pub fn find_quine_brute_force(&self, limit: usize) -> Option<usize> {
+        let mut computer = self.clone();
+
+        (0..limit).find(|&a| {
+            computer.reset(a, self.b, self.c);
+
+            let mut expected = self.program.iter();
+            while let Some(value) = computer.until_out() {
+                if expected.next() != Some(&value) {
+                    return false;
+                }
+            }
+
+            expected.next().is_none()
+        })
+    }
+
+    /// Finds the smallest starting value of register a which makes the program
+    /// output a copy of itself, for any program.
+    ///
+    /// Tries each length of a in turn, fixing its bits from the top down with
+    /// zeros first, so the first value found is the smallest. After each bit
+    /// is fixed, the program is run with the unknown bits left unknown, and the
+    /// search backtracks as soon as a known output bit is wrong or the program
+    /// outputs the wrong number of values.
+    ///
+    /// This makes no assumptions about the program's shape, but it's slower than
+    /// [`ThreeBitComputer::find_quine`]. It takes exponential time for programs
+    /// whose outputs are only known once nearly all of a is.
+    pub fn find_quine_by_bits(&self) -> Option<usize> {
+        (0..=usize::BITS).find_map(|length| {
+            if length == 0 {
+                let a = PartialValue::known(0);
+                return (self.check_partial(a) == Verdict::Matches).then_some(0);
+            }
+
+            let top = 1 << (length - 1);
+            let a = PartialValue {
+                known: !(top - 1),
+                value: top,
+            };
+
+            self.search_bits(a, length - 1)
+        })
+    }
+
+    /// Fixes the lowest `unknown` bits of a, which are the only unknown ones
+    fn search_bits(&self, a: PartialValue, unknown: u32) -> Option<usize> {
+        match self.check_partial(a) {
+            Verdict::Mismatch => return None,
+            Verdict::Matches if unknown == 0 => return Some(a.value),
+            _ if unknown == 0 => return None,
+            _ => {}
+        }
+
+        let bit = 1 << (unknown - 1);
+        [0, bit].into_iter().find_map(|value| {
+            let a = PartialValue {
+                known: a.known | bit,
+                value: a.value | value,
+            };
+
+            self.search_bits(a, unknown - 1)
+        })
+    }
+
+    /// Runs the program with some bits of a unknown, checking each output
+    /// against the program as far as the known bits allow
+    fn check_partial(&self, a: PartialValue) -> Verdict {
+        // generous enough for any real program, while stopping ones which loop
+        // forever on a known value of a
+        const MAX_STEPS: usize = 1_000_000;
+
+        let combo = |operand: ComboOperand, registers: [PartialValue; 3]| match operand {
+            ComboOperand::Literal(value) => Some(PartialValue::known(value)),
+            ComboOperand::A => Some(registers[0]),
+            ComboOperand::B => Some(registers[1]),
+            ComboOperand::C => Some(registers[2]),
+            ComboOperand::Reserved => None,
+        };
+
+        let mut registers = [a, PartialValue::known(self.b), PartialValue::known(self.c)];
+        let mut pc = 0;
+        let mut outputs = 0;
+
+        for _ in 0..MAX_STEPS {
+            if pc + 1 >= self.program.len() {
+                return if outputs == self.program.len() {
+                    Verdict::Matches
+                } else {
+                    Verdict::Mismatch
+                };
+            }
+
+            let Some(instruction) =
+                ThreeBitInstruction::decode(self.program[pc], self.program[pc + 1])
+            else {
+                return Verdict::Mismatch;
+            };
+            // the real machine panics on a reserved combo operand, but only
+            // instructions which take a combo operand read it
+            let operand = match combo(instruction.combo(), registers) {
+                Some(operand) => operand,
+                None if instruction.opcode.takes_combo() => return Verdict::Mismatch,
+                None => PartialValue::known(0),
+            };
+
+            let [a, b, c] = &mut registers;
+            match instruction.opcode {
+                Opcode::Adv => *a = a.shift_right(operand),
+                Opcode::Bxl => *b = b.xor(PartialValue::known(instruction.operand)),
+                Opcode::Bst => *b = operand.low_bits(),
+                Opcode::Jnz => match a.is_zero() {
+                    Some(false) => {
+                        pc = instruction.operand;
+                        continue;
+                    }
+                    Some(true) => {}
+                    None => return Verdict::Unknown,
+                },
+                Opcode::Bxc => *b = b.xor(*c),
+                Opcode::Out => {
+                    let Some(&expected) = self.program.get(outputs) else {
+                        return Verdict::Mismatch;
+                    };
+
+                    let value = operand.low_bits();
+                    if (value.value ^ expected) & value.known & 7 != 0 {
+                        return Verdict::Mismatch;
+                    }
+                    outputs += 1;
+                }
+                Opcode::Bdv => *b = a.shift_right(operand),
+                Opcode::Cdv => *c = a.shift_right(operand),
+            }
+
+            pc += 2;
+        }
+
+        Verdict::Unknown
+    }
+}
+
+/// The outcome of running a program with some bits of a unknown
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Verdict {
+    /// Whatever the unknown bits are, the program doesn't output itself
+    Mismatch,
+    /// The program outputs exactly itself
+    Matches,
+    /// It depends on the unknown bits
+    Unknown,
+}
+
+/// A register where only the bits set in `known` are known, with their
+/// values in `value`. Unknown bits of `value` are always 0.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct PartialValue {
+    known: usize,
+    value: usize,
+}
+
+impl PartialValue {
+    /// The most values an unknown shift amount is expanded into before giving
+    /// up and treating the result as entirely unknown
+    const MAX_SHIFT_CANDIDATES: usize = 16;
+
+    fn known(value: usize) -> Self {
+        Self {
+            known: usize::MAX,
+            value,
+        }
+    }
+
+    fn is_known(self) -> bool {
+        self.known == usize::MAX
+    }
+
+    fn xor(self, other: Self) -> Self {
+        let known = self.known & other.known;
+        Self {
+            known,
+            value: (self.value ^ other.value) & known,
+        }
+    }
+
+    /// The value modulo 8
+    fn low_bits(self) -> Self {
+        Self {
+            known: self.known | !7,
+            value: self.value & 7,
+        }
+    }
+
+    /// `Some(true)` or `Some(false)` if it's known whether the value is zero
+    fn is_zero(self) -> Option<bool> {
+        if self.value != 0 {
+            Some(false)
+        } else if self.is_known() {
+            Some(true)
+        } else {
+            None
+        }
+    }
+
+    fn shift_right(self, amount: Self) -> Self {
+        let shift = |amount: usize| {
+            let amount = u32::try_from(amount).unwrap_or(u32::MAX);
+            Self {
+                known: self.known.checked_shr(amount).unwrap_or(0)
+                    | !usize::MAX.checked_shr(amount).unwrap_or(0),
+                value: self.value.checked_shr(amount).unwrap_or(0),
+            }
+        };
+
+        if amount.is_known() {
+            return shift(amount.value);
+        }
+
+        // Any shift of at least the register width gives 0, so only the low
+        // bits of the amount need trying, plus one amount that big if it can be
+        let width_bits = usize::BITS.trailing_zeros();
+        let low_mask = (1 << width_bits) - 1;
+        let unknown_low = !amount.known & low_mask;
+        if 1 << unknown_low.count_ones() > Self::MAX_SHIFT_CANDIDATES {
+            return Self { known: 0, value: 0 };
+        }
+
+        let mut candidates = vec![];
+        let mut subset = 0usize;
+        loop {
+            candidates.push(shift(amount.value & low_mask | subset));
+            // the next subset of the unknown low bits
+            subset = subset.wrapping_sub(unknown_low) & unknown_low;
+            if subset == 0 {
+                break;
+            }
+        }
+        if amount.value & !low_mask != 0 || !amount.known & !low_mask != 0 {
+            candidates.push(shift(usize::MAX));
+        }
+
+        candidates
+            .into_iter()
+            .reduce(|merged, candidate| {
+                let known = merged.known & candidate.known & !(merged.value ^ candidate.value);
+                Self {
+                    known,
+                    value: merged.value & known,
+                }
+            })
+            .unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "Register A: 2024
+Register B: 0
+Register C: 0
+
+Program: 0,3,5,4,3,0";
+
+    #[test]
+    fn disassemble_and_assemble() {
+        let computer = ThreeBitComputer::parse(EXAMPLE);
+        let listing = ThreeBitComputer::disassemble(&computer.program);
+
+        assert_eq!(
+            listing.to_string(),
+            "L0:\n    adv 3\n    out a\n    jnz L0\n"
+        );
+        assert_eq!(
+            ThreeBitComputer::assemble(&listing.to_string()).unwrap(),
+            computer.program
+        );
+
+        let program = ThreeBitComputer::assemble(
+            "bst a ; b = a % 8
+             bxl 1
+             cdv b
+             bxc
+             jnz 2",
+        )
+        .unwrap();
+        assert_eq!(program, vec![2, 4, 1, 1, 7, 5, 4, 0, 3, 2]);
+
+        assert!(ThreeBitComputer::assemble("adv 5").is_err());
+        assert!(ThreeBitComputer::assemble("jnz nowhere").is_err());
+        assert!(ThreeBitComputer::assemble("nop 1").is_err());
+    }
+
+    #[test]
+    fn breakpoints_and_trace() {
+        let mut computer = ThreeBitComputer::parse(EXAMPLE);
+        computer.breakpoints.insert(2);
+
+        let mut output = vec![];
+        assert_eq!(computer.resume(&mut output), StopReason::Breakpoint(2));
+        assert_eq!((computer.a, output.len()), (253, 0));
+
+        assert_eq!(computer.resume(&mut output), StopReason::Breakpoint(2));
+        assert_eq!(output, vec![5]);
+
+        computer.breakpoints.clear();
+        assert_eq!(computer.resume(&mut output), StopReason::Halted);
+        assert_eq!(output, vec![5, 7, 3, 0]);
+
+        computer.reset(2024, 0, 0);
+        let trace = computer.trace(4);
+        assert_eq!(trace.len(), 4);
+        assert_eq!(trace[1].output, Some(5));
+        assert_eq!(trace[3].instruction.opcode, Opcode::Adv);
+        assert_eq!(trace[3].a, 31);
+    }
+
+    #[test]
+    fn finds_quines() {
+        let computer = ThreeBitComputer::parse(
+            "Register A: 2024
+Register B: 0
+Register C: 0
+
+Program: 0,3,5,4,3,0",
+        );
+
+        assert_eq!(computer.loop_shift(), Ok(3));
+        assert_eq!(computer.find_quine(), Ok(117440));
+        assert_eq!(computer.find_quine_brute_force(200_000), Some(117440));
+
+        // the usual shape of the real puzzle inputs
+        let program = ThreeBitComputer::assemble(
+            "bst a
+             bxl 5
+             cdv b
+             bxl 6
+             adv 3
+             bxc
+             out b
+             jnz 0",
+        )
+        .unwrap();
+        let computer = ThreeBitComputer::new(program.clone(), 0, 0, 0);
+        assert_eq!(computer.loop_shift(), Ok(3));
+
+        let a = computer.find_quine().unwrap();
+        assert_eq!(a, 105_843_716_614_554);
+        let mut check = ThreeBitComputer::new(program, a, 0, 0);
+        assert_eq!(check.run(), check.program);
+    }
+
+    #[test]
+    fn reports_broken_assumptions() {
+        let check = |source: &str| {
+            let program = ThreeBitComputer::assemble(source).unwrap();
+            ThreeBitComputer::new(program, 0, 0, 0).find_quine()
+        };
+
+        assert_eq!(check("adv 3\nout a"), Err(QuineError::NotASingleLoop));
+        assert_eq!(
+            check("bst a\nadv b\nout a\njnz 0"),
+            Err(QuineError::VariableShift)
+        );
+        assert_eq!(check("out a\njnz 0"), Err(QuineError::VariableShift));
+        assert_eq!(
+            check("adv 1\nout a\nout a\njnz 0"),
+            Err(QuineError::OutputsPerIteration(2))
+        );
+        assert_eq!(
+            check("bxl 1\nadv 3\nout b\njnz 0"),
+            Err(QuineError::CarriedRegister('b'))
+        );
+        assert_eq!(check("adv 3\nout 1\njnz 0"), Err(QuineError::NoSolution));
+    }
+
+    #[test]
+    fn finds_quines_bit_by_bit() {
+        let quine = |source: &str| {
+            let program = ThreeBitComputer::assemble(source).unwrap();
+            ThreeBitComputer::new(program, 0, 0, 0).find_quine_by_bits()
+        };
+
+        // programs the chunk by chunk search handles give the same answers
+        assert_eq!(quine("adv 3\nout a\njnz 0"), Some(117440));
+        assert_eq!(
+            quine("bst a\nbxl 5\ncdv b\nbxl 6\nadv 3\nbxc\nout b\njnz 0"),
+            Some(105_843_716_614_554)
+        );
+        assert_eq!(quine("adv 3\nout 1\njnz 0"), None);
+
+        // b is carried between iterations, so only this search works
+        let program = ThreeBitComputer::assemble("bxl 2\nbst b\nadv 3\nout a\njnz 0").unwrap();
+        let computer = ThreeBitComputer::new(program.clone(), 0, 0, 0);
+        assert_eq!(computer.find_quine(), Err(QuineError::CarriedRegister('b')));
+
+        let a = computer.find_quine_by_bits().unwrap();
+        assert_eq!(a, 481_055_880);
+        let mut check = ThreeBitComputer::new(program, a, 0, 0);
+        assert_eq!(check.run(), check.program);
+        assert_eq!(computer.find_quine_brute_force(1 << 16), None);
+    }
+
+    #[test]
+    fn partial_values() {
+        // the low 2 bits are unknown
+        let a = PartialValue {
+            known: !3,
+            value: 0b1100,
+        };
+
+        assert_eq!(a.is_zero(), Some(false));
+        assert_eq!(
+            a.low_bits(),
+            PartialValue {
+                known: !3,
+                value: 4
+            }
+        );
+        assert_eq!(
+            a.shift_right(PartialValue::known(2)),
+            PartialValue::known(0b11)
+        );
+
+        // 0b11?? and 0b011? only agree on bit 2 and the bits above 3
+        let amount = PartialValue {
+            known: !1,
+            value: 0,
+        };
+        assert_eq!(
+            a.shift_right(amount),
+            PartialValue {
+                known: !0b1011,
+                value: 0b100,
+            }
+        );
+        assert_eq!(
+            PartialValue {
+                known: !1,
+                value: 0
+            }
+            .is_zero(),
+            None
+        );
+    }
+}
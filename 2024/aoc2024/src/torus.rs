@@ -0,0 +1,240 @@
+use crate::{chinese_remainder, lcm, Grid2, Vector2D};
+
+/// The side length of the squares robots are counted in when working out the
+/// entropy of a frame. Small enough to notice a picture forming, big enough
+/// that robots in neighbouring cells count as being together.
+const ENTROPY_BLOCK: i32 = 4;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TorusRobot {
+    pub position: Vector2D<i32>,
+    pub velocity: Vector2D<i32>,
+}
+
+/// How spread out the robots are in a single frame. Lower is more ordered.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FrameScore {
+    pub x_variance: f64,
+    pub y_variance: f64,
+    /// The Shannon entropy, in bits, of which block each robot is in
+    pub entropy: f64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Axis {
+    X,
+    Y,
+}
+
+/// Robots moving in straight lines at constant speed on a grid which wraps
+/// around at the edges, like in 2024 day 14.
+///
+/// Along each axis the robots return to where they started after `width` or
+/// `height` seconds, so each axis can be looked at on its own and the results
+/// combined with the Chinese remainder theorem.
+#[derive(Clone, Debug)]
+pub struct Torus {
+    pub width: i32,
+    pub height: i32,
+    pub robots: Vec<TorusRobot>,
+}
+
+impl Torus {
+    pub fn new(width: i32, height: i32, robots: Vec<TorusRobot>) -> Self {
+        assert!(
+            width > 0 && height > 0,
+            "The torus must have a positive size"
+        );
+
+        Self {
+            width,
+            height,
+            robots,
+        }
+    }
+
+    /// The number of seconds before every robot is back where it started
+    pub fn period(&self) -> u64 {
+        lcm(self.width as u64, self.height as u64)
+    }
+
+    fn size(&self, axis: Axis) -> i32 {
+        match axis {
+            Axis::X => self.width,
+            Axis::Y => self.height,
+        }
+    }
+
+    fn coordinate(&self, robot: &TorusRobot, time: u64, axis: Axis) -> i32 {
+        let size = self.size(axis);
+        let (position, velocity) = match axis {
+            Axis::X => (robot.position.x, robot.velocity.x),
+            Axis::Y => (robot.position.y, robot.velocity.y),
+        };
+
+        // only the time modulo the size matters, which keeps this from overflowing
+        let time = (time % size as u64) as i64;
+        (position as i64 + velocity as i64 * time).rem_euclid(size as i64) as i32
+    }
+
+    pub fn position(&self, robot: &TorusRobot, time: u64) -> Vector2D<i32> {
+        Vector2D::new(
+            self.coordinate(robot, time, Axis::X),
+            self.coordinate(robot, time, Axis::Y),
+        )
+    }
+
+    pub fn positions_at(&self, time: u64) -> Vec<Vector2D<i32>> {
+        self.robots
+            .iter()
+            .map(|robot| self.position(robot, time))
+            .collect()
+    }
+
+    /// The number of robots on each cell after `time` seconds
+    pub fn frame(&self, time: u64) -> Grid2<u32> {
+        let mut grid = Grid2::new(self.width as usize, self.height as usize);
+        for position in self.positions_at(time) {
+            grid.points[position.y as usize][position.x as usize] += 1;
+        }
+
+        grid
+    }
+
+    /// `n² × variance` of the robots' positions along one axis, which orders
+    /// frames the same way as the variance but stays an integer
+    fn spread(&self, time: u64, axis: Axis) -> i64 {
+        let n = self.robots.len() as i64;
+        let (sum, sum_of_squares) = self.robots.iter().fold((0, 0), |(sum, squares), robot| {
+            let value = self.coordinate(robot, time, axis) as i64;
+            (sum + value, squares + value * value)
+        });
+
+        n * sum_of_squares - sum * sum
+    }
+
+    pub fn score(&self, time: u64) -> FrameScore {
+        let n = self.robots.len().max(1) as f64;
+        let variance = |axis| self.spread(time, axis) as f64 / (n * n);
+
+        let blocks_across = (self.width + ENTROPY_BLOCK - 1) / ENTROPY_BLOCK;
+        let blocks_down = (self.height + ENTROPY_BLOCK - 1) / ENTROPY_BLOCK;
+        let mut counts = vec![0u32; (blocks_across * blocks_down) as usize];
+        for position in self.positions_at(time) {
+            let block = position.x / ENTROPY_BLOCK + position.y / ENTROPY_BLOCK * blocks_across;
+            counts[block as usize] += 1;
+        }
+
+        let entropy = counts
+            .iter()
+            .filter(|&&count| count > 0)
+            .map(|&count| {
+                let p = count as f64 / n;
+                -p * p.log2()
+            })
+            .sum();
+
+        FrameScore {
+            x_variance: variance(Axis::X),
+            y_variance: variance(Axis::Y),
+            entropy,
+        }
+    }
+
+    /// The first time, within one pass of the axis, where the robots are most
+    /// bunched up along it
+    fn tightest_on_axis(&self, axis: Axis) -> u64 {
+        (0..self.size(axis) as u64)
+            .min_by_key(|&time| self.spread(time, axis))
+            .unwrap()
+    }
+
+    /// The first time where the robots are most bunched up along both axes at
+    /// once, which is where they form a picture.
+    ///
+    /// Returns `None` if the width and height share a factor and the times for
+    /// each axis never line up.
+    pub fn most_ordered_time(&self) -> Option<u64> {
+        let x_time = self.tightest_on_axis(Axis::X);
+        let y_time = self.tightest_on_axis(Axis::Y);
+
+        let (time, _) = chinese_remainder([
+            (x_time as i64, self.width as i64),
+            (y_time as i64, self.height as i64),
+        ])?;
+
+        Some(time as u64)
+    }
+
+    /// The frame after `time` seconds as a binary greyscale PGM image, with
+    /// robots in white
+    pub fn to_pgm(&self, time: u64) -> Vec<u8> {
+        let mut image = format!("P5\n{} {}\n255\n", self.width, self.height).into_bytes();
+
+        let frame = self.frame(time);
+        image.extend(
+            frame
+                .points
+                .iter()
+                .flatten()
+                .map(|&count| if count > 0 { 255 } else { 0 }),
+        );
+
+        image
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Robots which all meet at (5, 3) after `time` seconds, with velocities
+    /// that are all different along both axes so they don't meet at any other time
+    fn converging(time: i32) -> Torus {
+        let robots = (0..7)
+            .map(|i| {
+                let velocity = Vector2D::new(i - 3, 2 * i - 5);
+                TorusRobot {
+                    position: Vector2D::new(5, 3) - velocity * time,
+                    velocity,
+                }
+            })
+            .collect();
+
+        Torus::new(11, 7, robots)
+    }
+
+    #[test]
+    fn finds_the_ordered_frame() {
+        let torus = converging(40);
+
+        assert_eq!(torus.period(), 77);
+        assert_eq!(torus.most_ordered_time(), Some(40));
+        assert!(torus.positions_at(40).iter().all(|&p| p == (5, 3).into()));
+        assert_eq!(torus.positions_at(40 + 77), torus.positions_at(40));
+
+        let ordered = torus.score(40);
+        assert_eq!(ordered.x_variance, 0.0);
+        assert_eq!(ordered.entropy, 0.0);
+
+        for time in (0..77).filter(|&time| time != 40) {
+            let score = torus.score(time);
+            assert!(score.x_variance + score.y_variance > 0.0);
+        }
+    }
+
+    #[test]
+    fn pgm_export() {
+        let torus = Torus::new(
+            3,
+            2,
+            vec![TorusRobot {
+                position: Vector2D::new(0, 0),
+                velocity: Vector2D::new(1, 1),
+            }],
+        );
+
+        assert_eq!(torus.frame(4).points, vec![vec![0, 1, 0], vec![0, 0, 0]]);
+        assert_eq!(torus.to_pgm(4), b"P5\n3 2\n255\n\x00\xff\x00\x00\x00\x00");
+    }
+}
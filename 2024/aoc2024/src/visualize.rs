@@ -0,0 +1,207 @@
+use std::{
+    env,
+    fmt::Write as _,
+    fs::File,
+    io::{self, BufWriter, IsTerminal, Write},
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::{Grid2, Rgb};
+
+enum Target {
+    /// Redraws each frame in place with colours, pausing between frames
+    Terminal,
+    /// Writes each frame one after the other as plain text, as fast as possible
+    Plain(Box<dyn Write>),
+}
+
+/// Shows the state of a grid simulation as it runs, so solutions can be watched.
+///
+/// In a terminal each frame is drawn over the last one using ANSI colours,
+/// paced so that frames don't go by faster than `frame_delay`. Otherwise
+/// the frames are written out as plain text, for looking through afterwards.
+pub struct Visualizer {
+    target: Target,
+    pub frame_delay: Duration,
+    last_frame: Option<Instant>,
+}
+
+impl Visualizer {
+    /// Draws frames in the terminal
+    pub fn terminal(frame_delay: Duration) -> Self {
+        Self {
+            target: Target::Terminal,
+            frame_delay,
+            last_frame: None,
+        }
+    }
+
+    /// Writes frames as plain text to `writer`
+    pub fn plain(writer: impl Write + 'static) -> Self {
+        Self {
+            target: Target::Plain(Box::new(writer)),
+            frame_delay: Duration::ZERO,
+            last_frame: None,
+        }
+    }
+
+    /// Looks for `--visualize` in the command line arguments. This draws in the
+    /// terminal, unless stdout has been redirected in which case the frames are
+    /// written there as plain text. `--visualize=<path>` writes plain text
+    /// frames to the given file instead.
+    pub fn from_args(frame_delay: Duration) -> io::Result<Option<Self>> {
+        for argument in env::args().skip(1) {
+            if argument == "--visualize" {
+                let visualizer = if io::stdout().is_terminal() {
+                    Self::terminal(frame_delay)
+                } else {
+                    Self::plain(io::stdout())
+                };
+
+                return Ok(Some(visualizer));
+            }
+
+            if let Some(path) = argument.strip_prefix("--visualize=") {
+                return Ok(Some(Self::plain(BufWriter::new(File::create(path)?))));
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Draws a frame `width` cells across and `height` cells down, where `cell`
+    /// gives the character and colour at `(x, y)`, under a line of text
+    pub fn frame(
+        &mut self,
+        caption: &str,
+        width: usize,
+        height: usize,
+        cell: impl Fn(usize, usize) -> (char, Rgb),
+    ) -> io::Result<()> {
+        match &mut self.target {
+            Target::Terminal => {
+                if let Some(last_frame) = self.last_frame {
+                    thread::sleep(self.frame_delay.saturating_sub(last_frame.elapsed()));
+                } else {
+                    // clear the screen and hide the cursor
+                    print!("\x1b[2J\x1b[?25l");
+                }
+
+                let mut stdout = io::stdout().lock();
+                stdout.write_all(render_ansi(caption, width, height, cell).as_bytes())?;
+                stdout.flush()?;
+            }
+            Target::Plain(writer) => {
+                writer.write_all(render_plain(caption, width, height, cell).as_bytes())?;
+            }
+        }
+
+        self.last_frame = Some(Instant::now());
+        Ok(())
+    }
+
+    pub fn frame_grid<T>(
+        &mut self,
+        caption: &str,
+        grid: &Grid2<T>,
+        cell: impl Fn(&T) -> (char, Rgb),
+    ) -> io::Result<()> {
+        self.frame(caption, grid.width, grid.height, |x, y| {
+            cell(&grid.points[y][x])
+        })
+    }
+}
+
+impl Drop for Visualizer {
+    fn drop(&mut self) {
+        match &mut self.target {
+            // show the cursor again
+            Target::Terminal if self.last_frame.is_some() => print!("\x1b[?25h"),
+            Target::Terminal => {}
+            Target::Plain(writer) => {
+                let _ = writer.flush();
+            }
+        }
+    }
+}
+
+/// A frame which starts by moving the cursor to the top left, with escape codes
+/// to change the colour only where it differs from the previous cell
+fn render_ansi(
+    caption: &str,
+    width: usize,
+    height: usize,
+    cell: impl Fn(usize, usize) -> (char, Rgb),
+) -> String {
+    // move to the top left, then clear each line after writing it in case the
+    // last frame was longer
+    let mut frame = format!("\x1b[H\x1b[0m{caption}\x1b[K\n");
+
+    for y in 0..height {
+        let mut current_colour = None;
+
+        for x in 0..width {
+            let (symbol, colour) = cell(x, y);
+            if current_colour != Some(colour) {
+                let [r, g, b] = colour;
+                write!(frame, "\x1b[38;2;{r};{g};{b}m").unwrap();
+                current_colour = Some(colour);
+            }
+
+            frame.push(symbol);
+        }
+
+        frame.push_str("\x1b[0m\x1b[K\n");
+    }
+
+    frame
+}
+
+fn render_plain(
+    caption: &str,
+    width: usize,
+    height: usize,
+    cell: impl Fn(usize, usize) -> (char, Rgb),
+) -> String {
+    let mut frame = format!("{caption}\n");
+
+    for y in 0..height {
+        frame.extend((0..width).map(|x| cell(x, y).0));
+        frame.push('\n');
+    }
+
+    frame.push('\n');
+    frame
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RED: Rgb = [255, 0, 0];
+    const GREY: Rgb = [128, 128, 128];
+
+    fn cell(x: usize, y: usize) -> (char, Rgb) {
+        if (x, y) == (1, 0) {
+            ('@', RED)
+        } else {
+            ('#', GREY)
+        }
+    }
+
+    #[test]
+    fn plain_frames() {
+        assert_eq!(render_plain("step 1", 3, 2, cell), "step 1\n#@#\n###\n\n");
+    }
+
+    #[test]
+    fn ansi_frames() {
+        assert_eq!(
+            render_ansi("step 1", 3, 2, cell),
+            "\x1b[H\x1b[0mstep 1\x1b[K\n\
+             \x1b[38;2;128;128;128m#\x1b[38;2;255;0;0m@\x1b[38;2;128;128;128m#\x1b[0m\x1b[K\n\
+             \x1b[38;2;128;128;128m###\x1b[0m\x1b[K\n"
+        );
+    }
+}
@@ -1,3 +1,5 @@
+use aoc2025::{Checked, CheckedSumExt};
+
 fn main() {
     let input = aoc2025::get_input(2);
 
@@ -8,13 +10,13 @@ fn main() {
 fn part1(input: &str) -> u64 {
     parse(input)
         .map(|(start, end)| invalid_in_range(start, end))
-        .sum()
+        .checked_sum()
 }
 
 fn part2(input: &str) -> u64 {
     parse(input)
         .map(|(start, end)| invalid_in_range2(start, end))
-        .sum()
+        .checked_sum()
 }
 
 fn parse(input: &str) -> impl Iterator<Item = (u64, u64)> {
@@ -28,7 +30,7 @@ fn parse(input: &str) -> impl Iterator<Item = (u64, u64)> {
 }
 
 fn invalid_in_range(start: u64, end: u64) -> u64 {
-    let mut sum = 0;
+    let mut sum = Checked(0);
 
     for value in start..=end {
         // get the number of digits in value
@@ -38,20 +40,20 @@ fn invalid_in_range(start: u64, end: u64) -> u64 {
         }
 
         // it's a repeat if it's a multiple of 10001 (for some number of 0s)
-        let check = 10_u64.pow(digits / 2) + 1;
+        let check = (Checked(10_u64).pow(digits / 2) + 1).0;
 
         if value.is_multiple_of(check) {
             sum += value;
         }
     }
 
-    sum
+    sum.0
 }
 
 fn invalid_in_range2(start: u64, end: u64) -> u64 {
     (start..=end)
         .filter(|&value| is_probably_invalid2(value))
-        .sum()
+        .checked_sum()
 }
 
 fn is_probably_invalid2(value: u64) -> bool {
@@ -1,5 +1,7 @@
 use std::ops::RangeInclusive;
 
+use aoc2025::RangeSet;
+
 fn main() {
     let input = aoc2025::get_input(5);
     let (fresh_ranges, ingredients) = parse(&input);
@@ -9,39 +11,20 @@ fn main() {
 }
 
 fn part1(fresh_ranges: &[RangeInclusive<u64>], ingredients: &[u64]) -> usize {
+    let fresh: RangeSet<_> = fresh_ranges.iter().cloned().collect();
+
     ingredients
         .iter()
-        .filter(|ingredient| fresh_ranges.iter().any(|r| r.contains(ingredient)))
+        .filter(|&&ingredient| fresh.contains(ingredient))
         .count()
 }
 
 fn part2(fresh_ranges: &[RangeInclusive<u64>]) -> u64 {
-    let mut fresh_ranges = Vec::from(fresh_ranges);
-    fresh_ranges.sort_unstable_by_key(|r| *r.start());
-
-    // we want to deduplicate the ranges and combine overlapping ones
-    // with the ranges sorted by start point, we can consider them in pairs
-    let mut current = 0;
-    while current < fresh_ranges.len() - 1 {
-        let first = &fresh_ranges[current];
-        let second = &fresh_ranges[current + 1];
-
-        if first.end() >= second.start() {
-            // the two ranges overlap
-            let combined = *first.start()..=*second.end().max(first.end());
-
-            fresh_ranges[current] = combined;
-            fresh_ranges.remove(current + 1);
-        } else {
-            current += 1;
-        }
-    }
-
-    // now we can count the number of elements in each range
     fresh_ranges
         .iter()
-        .map(|range| *range.end() - *range.start() + 1)
-        .sum()
+        .cloned()
+        .collect::<RangeSet<_>>()
+        .total_length()
 }
 
 fn parse(input: &str) -> (Vec<RangeInclusive<u64>>, Vec<u64>) {
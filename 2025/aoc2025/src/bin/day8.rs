@@ -1,102 +1,38 @@
-use std::{
-    cmp::Reverse,
-    collections::{HashMap, HashSet},
-};
+use std::cmp::Reverse;
 
-use aoc2025::AllPairsExt;
+use aoc2025::{AllPairsExt, UnionFind};
 
 fn main() {
     let input = aoc2025::get_input(8);
 
     let (positions, sorted_pairs) = parse(&input);
-    println!("Part 1: {}", part1(&sorted_pairs, 1000));
+    println!("Part 1: {}", part1(&positions, &sorted_pairs, 1000));
     println!("Part 2: {}", part2(&positions, &sorted_pairs));
 }
 
-fn part1(sorted_pairs: &[(usize, usize)], connections: usize) -> usize {
-    let circuits = build_circuits(&sorted_pairs[..connections], |_| false).unwrap();
+fn part1(positions: &[[u64; 3]], sorted_pairs: &[(usize, usize)], connections: usize) -> usize {
+    let mut circuits = UnionFind::new(positions.len());
+    circuits.union_all(sorted_pairs[..connections].iter().copied());
 
-    let mut island_sizes = circuits
-        .values()
-        .map(|c| Reverse(c.len()))
+    let mut circuit_sizes = circuits
+        .component_sizes()
+        .into_iter()
+        .map(Reverse)
         .collect::<Vec<_>>();
-    island_sizes.sort_unstable();
+    circuit_sizes.sort_unstable();
 
-    island_sizes.iter().take(3).map(|s| s.0).product::<usize>()
+    circuit_sizes.iter().take(3).map(|s| s.0).product::<usize>()
 }
 
 fn part2(positions: &[[u64; 3]], sorted_pairs: &[(usize, usize)]) -> u64 {
-    let (a, b) = build_circuits(sorted_pairs, |circuits| {
-        circuits.len() == 1 && circuits.values().next().unwrap().len() == positions.len()
-    })
-    .unwrap_err();
+    let mut circuits = UnionFind::new(positions.len());
+    let (a, b) = circuits
+        .connect_until_single(sorted_pairs.iter().copied())
+        .unwrap();
 
     positions[a][0] * positions[b][0]
 }
 
-// returns Err with the `a, b` combo that resulted in the early_exit_check failing
-// or Ok(circuit) if it fully builds
-fn build_circuits(
-    sorted_pairs: &[(usize, usize)],
-    early_exit_check: impl Fn(&HashMap<usize, HashSet<usize>>) -> bool,
-) -> Result<HashMap<usize, HashSet<usize>>, (usize, usize)> {
-    // map id to the circuit it's in
-    let mut node_to_circuit = HashMap::new();
-    let mut next_circuit_id = 0usize;
-
-    let mut circuits: HashMap<usize, HashSet<_>> = HashMap::new();
-
-    for &(a, b) in sorted_pairs {
-        let circuit_a = node_to_circuit.get(&a).copied();
-        let circuit_b = node_to_circuit.get(&b).copied();
-
-        match (circuit_a, circuit_b) {
-            (None, None) => {
-                node_to_circuit.insert(a, next_circuit_id);
-                node_to_circuit.insert(b, next_circuit_id);
-                let c = circuits.entry(next_circuit_id).or_default();
-                c.insert(a);
-                c.insert(b);
-
-                next_circuit_id += 1;
-            }
-            (Some(circuit_a), None) => {
-                node_to_circuit.insert(b, circuit_a);
-                circuits.entry(circuit_a).or_default().insert(b);
-            }
-            (None, Some(circuit_b)) => {
-                node_to_circuit.insert(a, circuit_b);
-                circuits.entry(circuit_b).or_default().insert(a);
-            }
-            (Some(circuit_a), Some(circuit_b)) => {
-                // need to combine these...
-                if circuit_a == circuit_b {
-                    continue;
-                }
-
-                let [Some(island_a), Some(island_b)] =
-                    circuits.get_disjoint_mut([&circuit_a, &circuit_b])
-                else {
-                    panic!("Couldn't find entries");
-                };
-
-                island_b.drain().for_each(|b_entry| {
-                    island_a.insert(b_entry);
-                    node_to_circuit.insert(b_entry, circuit_a);
-                });
-
-                circuits.remove(&circuit_b);
-            }
-        }
-
-        if early_exit_check(&circuits) {
-            return Err((a, b));
-        }
-    }
-
-    Ok(circuits)
-}
-
 fn parse(input: &str) -> (Vec<[u64; 3]>, Vec<(usize, usize)>) {
     let positions: Vec<[u64; 3]> = input
         .lines()
@@ -149,9 +85,9 @@ const TEST_INPUT: &str = "162,817,812
 
 #[test]
 fn test_part1() {
-    let (_, sorted_pairs) = parse(TEST_INPUT);
+    let (positions, sorted_pairs) = parse(TEST_INPUT);
 
-    assert_eq!(part1(&sorted_pairs, 10), 40);
+    assert_eq!(part1(&positions, &sorted_pairs, 10), 40);
 }
 
 #[test]
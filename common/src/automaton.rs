@@ -0,0 +1,362 @@
+use std::collections::{HashMap, VecDeque};
+
+/// An occurrence of one of the patterns in a [`PatternSet`], covering the
+/// bytes `start..end` of the input
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct PatternMatch {
+    pub pattern: usize,
+    pub start: usize,
+    pub end: usize,
+}
+
+#[derive(Clone, Debug, Default)]
+struct Node {
+    children: HashMap<u8, usize>,
+    /// The node for the longest proper suffix of this one which is also in the trie
+    fail: usize,
+    /// The pattern which ends exactly at this node
+    pattern: Option<usize>,
+    /// The nearest node along the chain of `fail` links which ends a pattern
+    output: Option<usize>,
+}
+
+/// A set of patterns compiled into an Aho–Corasick automaton, which finds
+/// every occurrence of every pattern in a single pass over the input.
+///
+/// On top of plain searching, this can split an input into a sequence of
+/// patterns, like building a design out of towels with particular stripes.
+#[derive(Clone, Debug)]
+pub struct PatternSet {
+    patterns: Vec<String>,
+    nodes: Vec<Node>,
+}
+
+impl PatternSet {
+    pub fn new(patterns: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        let patterns: Vec<String> = patterns.into_iter().map(Into::into).collect();
+        let mut nodes = vec![Node::default()];
+
+        // build the trie
+        for (index, pattern) in patterns.iter().enumerate() {
+            assert!(!pattern.is_empty(), "Patterns can't be empty");
+
+            let mut current = 0;
+            for &byte in pattern.as_bytes() {
+                current = match nodes[current].children.get(&byte) {
+                    Some(&child) => child,
+                    None => {
+                        nodes.push(Node::default());
+                        let child = nodes.len() - 1;
+                        nodes[current].children.insert(byte, child);
+                        child
+                    }
+                };
+            }
+
+            nodes[current].pattern.get_or_insert(index);
+        }
+
+        // then add the failure links breadth first, so that every shorter
+        // suffix has already been linked
+        let mut queue: VecDeque<usize> = nodes[0].children.values().copied().collect();
+        while let Some(node) = queue.pop_front() {
+            let children: Vec<_> = nodes[node]
+                .children
+                .iter()
+                .map(|(&byte, &child)| (byte, child))
+                .collect();
+
+            for (byte, child) in children {
+                let mut fail = nodes[node].fail;
+                let child_fail = loop {
+                    if let Some(&next) = nodes[fail].children.get(&byte) {
+                        break next;
+                    }
+
+                    if fail == 0 {
+                        break 0;
+                    }
+
+                    fail = nodes[fail].fail;
+                };
+
+                nodes[child].fail = child_fail;
+                nodes[child].output = if nodes[child_fail].pattern.is_some() {
+                    Some(child_fail)
+                } else {
+                    nodes[child_fail].output
+                };
+
+                queue.push_back(child);
+            }
+        }
+
+        Self { patterns, nodes }
+    }
+
+    pub fn patterns(&self) -> &[String] {
+        &self.patterns
+    }
+
+    pub fn len(&self) -> usize {
+        self.patterns.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.patterns.is_empty()
+    }
+
+    fn step(&self, mut node: usize, byte: u8) -> usize {
+        loop {
+            if let Some(&next) = self.nodes[node].children.get(&byte) {
+                return next;
+            }
+
+            if node == 0 {
+                return 0;
+            }
+
+            node = self.nodes[node].fail;
+        }
+    }
+
+    /// Every occurrence of every pattern in `input`, including overlapping
+    /// ones, ordered by where they end
+    pub fn find_all(&self, input: &str) -> Vec<PatternMatch> {
+        let mut matches = vec![];
+        let mut node = 0;
+
+        for (i, &byte) in input.as_bytes().iter().enumerate() {
+            node = self.step(node, byte);
+
+            let mut found = if self.nodes[node].pattern.is_some() {
+                Some(node)
+            } else {
+                self.nodes[node].output
+            };
+
+            while let Some(output) = found {
+                let pattern = self.nodes[output].pattern.unwrap();
+                matches.push(PatternMatch {
+                    pattern,
+                    start: i + 1 - self.patterns[pattern].len(),
+                    end: i + 1,
+                });
+
+                found = self.nodes[output].output;
+            }
+        }
+
+        matches
+    }
+
+    /// Whether any pattern occurs anywhere in `input`
+    pub fn is_match(&self, input: &str) -> bool {
+        let mut node = 0;
+
+        input.bytes().any(|byte| {
+            node = self.step(node, byte);
+            self.nodes[node].pattern.is_some() || self.nodes[node].output.is_some()
+        })
+    }
+
+    /// For each position `i` in the input, the number of ways to split `input[..i]`
+    /// into a sequence of patterns
+    fn decomposition_counts(&self, input: &str) -> Vec<usize> {
+        let mut ways = vec![0; input.len() + 1];
+        ways[0] = 1;
+
+        // matches come out in order of their end, so everything before the
+        // start of a match has already been counted
+        for m in self.find_all(input) {
+            ways[m.end] += ways[m.start];
+        }
+
+        ways
+    }
+
+    /// Whether `input` can be made by joining together patterns, where each
+    /// pattern may be used any number of times
+    pub fn can_decompose(&self, input: &str) -> bool {
+        self.count_decompositions(input) > 0
+    }
+
+    /// The number of different ways to make `input` by joining together patterns
+    pub fn count_decompositions(&self, input: &str) -> usize {
+        self.decomposition_counts(input)[input.len()]
+    }
+
+    /// Every way of making `input` by joining together patterns, as the list
+    /// of pattern indices used for each one.
+    ///
+    /// There can be exponentially many of these, so prefer
+    /// [`PatternSet::count_decompositions`] if you only need how many there are.
+    pub fn decompositions(&self, input: &str) -> Vec<Vec<usize>> {
+        let ways = self.decomposition_counts(input);
+
+        let mut ending_at = vec![vec![]; input.len() + 1];
+        for m in self.find_all(input) {
+            if ways[m.start] > 0 {
+                ending_at[m.end].push(m);
+            }
+        }
+
+        // walk backwards from the end, only following matches which can be
+        // reached from the start
+        let mut result = vec![];
+        let mut stack = vec![(input.len(), vec![])];
+
+        while let Some((end, suffix)) = stack.pop() {
+            if end == 0 {
+                let mut decomposition: Vec<usize> = suffix;
+                decomposition.reverse();
+                result.push(decomposition);
+                continue;
+            }
+
+            for m in &ending_at[end] {
+                let mut suffix = suffix.clone();
+                suffix.push(m.pattern);
+                stack.push((m.start, suffix));
+            }
+        }
+
+        result.sort();
+        result
+    }
+}
+
+/// A nondeterministic finite automaton over bytes, which starts in state 0.
+///
+/// Beyond checking whether an input matches, this can count the paths through
+/// the automaton for inputs where some positions could be any of several bytes,
+/// like springs whose condition is unknown. For an automaton with at most one
+/// path for each concrete input, that's the number of matching inputs.
+#[derive(Clone, Debug)]
+pub struct Nfa {
+    transitions: Vec<Vec<(u8, usize)>>,
+    accepting: Vec<bool>,
+}
+
+impl Nfa {
+    pub fn new(states: usize) -> Self {
+        assert!(states > 0, "An automaton needs a start state");
+
+        Self {
+            transitions: vec![vec![]; states],
+            accepting: vec![false; states],
+        }
+    }
+
+    pub fn states(&self) -> usize {
+        self.transitions.len()
+    }
+
+    pub fn add_transition(&mut self, from: usize, byte: u8, to: usize) {
+        self.transitions[from].push((byte, to));
+    }
+
+    pub fn set_accepting(&mut self, state: usize) {
+        self.accepting[state] = true;
+    }
+
+    /// Whether there's any path through the automaton for `input` which ends
+    /// in an accepting state
+    pub fn is_match(&self, input: &str) -> bool {
+        self.count_paths(input.bytes().map(|byte| [byte])) > 0
+    }
+
+    /// The number of paths through the automaton ending in an accepting state,
+    /// where each position of the input is the set of bytes which could be there
+    pub fn count_paths(&self, input: impl IntoIterator<Item = impl AsRef<[u8]>>) -> usize {
+        let mut paths = vec![0; self.states()];
+        paths[0] = 1;
+
+        for options in input {
+            let options = options.as_ref();
+            let mut next = vec![0; self.states()];
+
+            for (state, &count) in paths.iter().enumerate().filter(|(_, &count)| count > 0) {
+                for &(byte, to) in &self.transitions[state] {
+                    if options.contains(&byte) {
+                        next[to] += count;
+                    }
+                }
+            }
+
+            paths = next;
+        }
+
+        paths
+            .iter()
+            .zip(&self.accepting)
+            .filter(|(_, &accepting)| accepting)
+            .map(|(&count, _)| count)
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_overlapping_matches() {
+        let patterns = PatternSet::new(["he", "she", "his", "hers"]);
+
+        let matches: Vec<_> = patterns
+            .find_all("ushers")
+            .into_iter()
+            .map(|m| (&patterns.patterns()[m.pattern][..], m.start))
+            .collect();
+        assert_eq!(matches, vec![("she", 1), ("he", 2), ("hers", 2)]);
+
+        assert!(patterns.is_match("this"));
+        assert!(!patterns.is_match("llama"));
+    }
+
+    #[test]
+    fn towel_decompositions() {
+        // from 2024 day 19
+        let towels = PatternSet::new("r, wr, b, g, bwu, rb, gb, br".split(", "));
+
+        assert_eq!(towels.count_decompositions("brwrr"), 2);
+        assert_eq!(towels.count_decompositions("rrbgbr"), 6);
+        assert!(towels.can_decompose("bggr"));
+        assert!(!towels.can_decompose("ubwu"));
+        assert!(!towels.can_decompose("bbrgwb"));
+
+        let names = |decomposition: &Vec<usize>| {
+            decomposition
+                .iter()
+                .map(|&i| &towels.patterns()[i][..])
+                .collect::<Vec<_>>()
+        };
+        let mut decompositions: Vec<_> = towels.decompositions("brwrr").iter().map(names).collect();
+        decompositions.sort();
+        assert_eq!(
+            decompositions,
+            vec![vec!["b", "r", "wr", "r"], vec!["br", "wr", "r"]]
+        );
+    }
+
+    #[test]
+    fn counts_paths_through_nfa() {
+        // a, then any number of bs, then c
+        let mut nfa = Nfa::new(3);
+        nfa.add_transition(0, b'a', 1);
+        nfa.add_transition(1, b'b', 1);
+        nfa.add_transition(1, b'c', 2);
+        nfa.set_accepting(2);
+
+        assert!(nfa.is_match("ac"));
+        assert!(nfa.is_match("abbbc"));
+        assert!(!nfa.is_match("abcb"));
+        assert!(!nfa.is_match(""));
+
+        let input: [&[u8]; 4] = [b"a", b"abc", b"bc", b"c"];
+        assert_eq!(nfa.count_paths(input), 1);
+        let input: [&[u8]; 3] = [b"a", b"bc", b"bc"];
+        assert_eq!(nfa.count_paths(input), 1);
+    }
+}
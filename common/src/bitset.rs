@@ -0,0 +1,212 @@
+use std::iter;
+
+/// A growable set of small integers, stored one bit per value.
+#[derive(Clone, Default, Debug)]
+pub struct BitSet {
+    words: Vec<u64>,
+}
+
+impl BitSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            words: vec![0; capacity.div_ceil(64)],
+        }
+    }
+
+    /// Creates a set containing every value in `0..len`.
+    pub fn full(len: usize) -> Self {
+        let mut set = Self::with_capacity(len);
+        for word in &mut set.words {
+            *word = u64::MAX;
+        }
+
+        if !len.is_multiple_of(64) {
+            *set.words.last_mut().unwrap() = (1 << (len % 64)) - 1;
+        }
+
+        set
+    }
+
+    /// Returns true if the value was not already in the set.
+    pub fn insert(&mut self, value: usize) -> bool {
+        let (word, bit) = (value / 64, value % 64);
+        if word >= self.words.len() {
+            self.words.resize(word + 1, 0);
+        }
+
+        let was_present = self.words[word] & (1 << bit) != 0;
+        self.words[word] |= 1 << bit;
+        !was_present
+    }
+
+    /// Returns true if the value was in the set.
+    pub fn remove(&mut self, value: usize) -> bool {
+        let (word, bit) = (value / 64, value % 64);
+        let Some(word) = self.words.get_mut(word) else {
+            return false;
+        };
+
+        let was_present = *word & (1 << bit) != 0;
+        *word &= !(1 << bit);
+        was_present
+    }
+
+    pub fn contains(&self, value: usize) -> bool {
+        self.words
+            .get(value / 64)
+            .is_some_and(|word| word & (1 << (value % 64)) != 0)
+    }
+
+    pub fn len(&self) -> usize {
+        self.words
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|&word| word == 0)
+    }
+
+    pub fn clear(&mut self) {
+        self.words.clear();
+    }
+
+    /// The smallest value in the set
+    pub fn first(&self) -> Option<usize> {
+        self.iter().next()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words.iter().enumerate().flat_map(|(i, &word)| {
+            let mut remaining = word;
+            iter::from_fn(move || {
+                if remaining == 0 {
+                    return None;
+                }
+
+                let bit = remaining.trailing_zeros() as usize;
+                remaining &= remaining - 1;
+                Some(i * 64 + bit)
+            })
+        })
+    }
+
+    pub fn union_with(&mut self, other: &BitSet) {
+        if other.words.len() > self.words.len() {
+            self.words.resize(other.words.len(), 0);
+        }
+
+        for (word, other) in self.words.iter_mut().zip(&other.words) {
+            *word |= other;
+        }
+    }
+
+    pub fn intersect_with(&mut self, other: &BitSet) {
+        for (i, word) in self.words.iter_mut().enumerate() {
+            *word &= other.words.get(i).copied().unwrap_or(0);
+        }
+    }
+
+    pub fn difference_with(&mut self, other: &BitSet) {
+        for (word, other) in self.words.iter_mut().zip(&other.words) {
+            *word &= !other;
+        }
+    }
+
+    pub fn intersection(&self, other: &BitSet) -> BitSet {
+        let mut result = self.clone();
+        result.intersect_with(other);
+        result
+    }
+
+    pub fn union(&self, other: &BitSet) -> BitSet {
+        let mut result = self.clone();
+        result.union_with(other);
+        result
+    }
+
+    pub fn difference(&self, other: &BitSet) -> BitSet {
+        let mut result = self.clone();
+        result.difference_with(other);
+        result
+    }
+
+    /// The number of values in both sets, without allocating the intersection
+    pub fn intersection_len(&self, other: &BitSet) -> usize {
+        self.words
+            .iter()
+            .zip(&other.words)
+            .map(|(a, b)| (a & b).count_ones() as usize)
+            .sum()
+    }
+}
+
+impl PartialEq for BitSet {
+    fn eq(&self, other: &Self) -> bool {
+        let (shorter, longer) = if self.words.len() < other.words.len() {
+            (&self.words, &other.words)
+        } else {
+            (&other.words, &self.words)
+        };
+
+        longer[..shorter.len()] == shorter[..] && longer[shorter.len()..].iter().all(|&w| w == 0)
+    }
+}
+
+impl Eq for BitSet {}
+
+impl FromIterator<usize> for BitSet {
+    fn from_iter<I: IntoIterator<Item = usize>>(iter: I) -> Self {
+        let mut set = BitSet::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl Extend<usize> for BitSet {
+    fn extend<I: IntoIterator<Item = usize>>(&mut self, iter: I) {
+        for value in iter {
+            self.insert(value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn insert_remove_and_iterate() {
+        let mut set = BitSet::new();
+
+        assert!(set.insert(3));
+        assert!(set.insert(130));
+        assert!(!set.insert(3));
+
+        assert!(set.contains(130));
+        assert!(!set.contains(64));
+        assert_eq!(set.iter().collect::<Vec<_>>(), &[3, 130]);
+
+        assert!(set.remove(3));
+        assert!(!set.remove(1000));
+        assert_eq!(set.len(), 1);
+    }
+
+    #[test]
+    fn set_operations() {
+        let a: BitSet = [1, 2, 3, 70].into_iter().collect();
+        let b: BitSet = [2, 3, 4].into_iter().collect();
+
+        assert_eq!(a.intersection(&b).iter().collect::<Vec<_>>(), &[2, 3]);
+        assert_eq!(a.union(&b).iter().collect::<Vec<_>>(), &[1, 2, 3, 4, 70]);
+        assert_eq!(a.difference(&b).iter().collect::<Vec<_>>(), &[1, 70]);
+        assert_eq!(a.intersection_len(&b), 2);
+
+        assert_eq!(BitSet::full(66).len(), 66);
+    }
+}
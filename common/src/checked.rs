@@ -0,0 +1,209 @@
+use std::{
+    any::type_name,
+    fmt::{self, Debug, Display},
+    ops::{Add, AddAssign, Div, Mul, MulAssign, Rem, Sub, SubAssign},
+    panic::Location,
+};
+
+/// An integer whose arithmetic panics on overflow in every build profile,
+/// rather than wrapping around in release builds and giving a wrong answer.
+///
+/// The panic message includes the file and line of the calculation, so it
+/// says which day overflowed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Checked<T>(pub T);
+
+#[track_caller]
+fn overflowed(a: impl Debug, operation: &str, b: impl Debug) -> ! {
+    panic!(
+        "Arithmetic overflow in {}: {a:?} {operation} {b:?}",
+        Location::caller()
+    )
+}
+
+/// Converts between integer types like `as` does, but panics if the value
+/// doesn't fit in the new type instead of silently truncating it
+#[track_caller]
+pub fn cast<T, U>(value: T) -> U
+where
+    T: Copy + Display,
+    U: TryFrom<T>,
+{
+    match U::try_from(value) {
+        Ok(converted) => converted,
+        Err(_) => panic!(
+            "Overflow in {}: {value} doesn't fit in a {}",
+            Location::caller(),
+            type_name::<U>()
+        ),
+    }
+}
+
+/// The integer operations which [`Checked`] needs
+pub trait CheckedInteger: Copy + Debug + Sized {
+    const ZERO: Self;
+
+    fn checked_add(self, other: Self) -> Option<Self>;
+    fn checked_sub(self, other: Self) -> Option<Self>;
+    fn checked_mul(self, other: Self) -> Option<Self>;
+    fn checked_div(self, other: Self) -> Option<Self>;
+    fn checked_rem(self, other: Self) -> Option<Self>;
+    fn checked_pow(self, exponent: u32) -> Option<Self>;
+}
+
+macro_rules! impl_checked_integer {
+    ($($t:ty),*) => {
+        $(
+            impl CheckedInteger for $t {
+                const ZERO: Self = 0;
+
+                fn checked_add(self, other: Self) -> Option<Self> {
+                    <$t>::checked_add(self, other)
+                }
+
+                fn checked_sub(self, other: Self) -> Option<Self> {
+                    <$t>::checked_sub(self, other)
+                }
+
+                fn checked_mul(self, other: Self) -> Option<Self> {
+                    <$t>::checked_mul(self, other)
+                }
+
+                fn checked_div(self, other: Self) -> Option<Self> {
+                    <$t>::checked_div(self, other)
+                }
+
+                fn checked_rem(self, other: Self) -> Option<Self> {
+                    <$t>::checked_rem(self, other)
+                }
+
+                fn checked_pow(self, exponent: u32) -> Option<Self> {
+                    <$t>::checked_pow(self, exponent)
+                }
+            }
+        )*
+    };
+}
+
+impl_checked_integer!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+impl<T: CheckedInteger> Checked<T> {
+    #[track_caller]
+    pub fn pow(self, exponent: u32) -> Self {
+        match self.0.checked_pow(exponent) {
+            Some(result) => Self(result),
+            None => overflowed(self.0, "^", exponent),
+        }
+    }
+}
+
+impl<T: Display> Display for Checked<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+macro_rules! impl_checked_op {
+    ($trait:ident, $method:ident, $checked:ident, $symbol:literal) => {
+        impl<T: CheckedInteger> $trait for Checked<T> {
+            type Output = Self;
+
+            #[track_caller]
+            fn $method(self, other: Self) -> Self {
+                match self.0.$checked(other.0) {
+                    Some(result) => Self(result),
+                    None => overflowed(self.0, $symbol, other.0),
+                }
+            }
+        }
+
+        impl<T: CheckedInteger> $trait<T> for Checked<T> {
+            type Output = Self;
+
+            #[track_caller]
+            fn $method(self, other: T) -> Self {
+                self.$method(Checked(other))
+            }
+        }
+    };
+}
+
+impl_checked_op!(Add, add, checked_add, "+");
+impl_checked_op!(Sub, sub, checked_sub, "-");
+impl_checked_op!(Mul, mul, checked_mul, "*");
+impl_checked_op!(Div, div, checked_div, "/");
+impl_checked_op!(Rem, rem, checked_rem, "%");
+
+macro_rules! impl_checked_assign_op {
+    ($trait:ident, $method:ident, $op:ident) => {
+        impl<T: CheckedInteger> $trait for Checked<T> {
+            #[track_caller]
+            fn $method(&mut self, other: Self) {
+                *self = self.$op(other);
+            }
+        }
+
+        impl<T: CheckedInteger> $trait<T> for Checked<T> {
+            #[track_caller]
+            fn $method(&mut self, other: T) {
+                *self = self.$op(other);
+            }
+        }
+    };
+}
+
+impl_checked_assign_op!(AddAssign, add_assign, add);
+impl_checked_assign_op!(SubAssign, sub_assign, sub);
+impl_checked_assign_op!(MulAssign, mul_assign, mul);
+
+/// Sums integers, panicking with the caller's location if the total overflows.
+///
+/// This is a separate method rather than an implementation of [`Sum`](std::iter::Sum)
+/// because `Iterator::sum` would hide where the overflowing sum was.
+pub trait CheckedSumExt<T> {
+    fn checked_sum(self) -> T;
+}
+
+impl<T: CheckedInteger, I: Iterator<Item = T>> CheckedSumExt<T> for I {
+    #[track_caller]
+    fn checked_sum(self) -> T {
+        let mut total = Checked(T::ZERO);
+        for value in self {
+            total += value;
+        }
+
+        total.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arithmetic_which_fits() {
+        assert_eq!(Checked(3_u64) * 4 + Checked(2), Checked(14));
+        assert_eq!(Checked(10_i64) - 12, Checked(-2));
+        assert_eq!(Checked(10_u32).pow(3), Checked(1000));
+        assert_eq!([u64::MAX - 1, 1].into_iter().checked_sum(), u64::MAX);
+        assert_eq!(cast::<i64, i32>(-5), -5);
+    }
+
+    #[test]
+    #[should_panic(expected = "Arithmetic overflow in common/src/checked.rs")]
+    fn panics_on_overflow() {
+        let _ = Checked(u64::MAX / 2) * 3;
+    }
+
+    #[test]
+    #[should_panic(expected = "4294967296 doesn't fit in a u32")]
+    fn panics_on_truncation() {
+        cast::<u64, u32>(1 << 32);
+    }
+
+    #[test]
+    #[should_panic(expected = "Arithmetic overflow in common/src/checked.rs")]
+    fn panics_when_sum_overflows() {
+        [u64::MAX, 1].into_iter().checked_sum();
+    }
+}
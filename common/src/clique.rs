@@ -0,0 +1,135 @@
+use crate::{BitSet, Graph};
+
+impl Graph {
+    /// Every maximal clique in the graph, found using Bron–Kerbosch with
+    /// pivoting. Each clique is returned with its nodes in ascending order.
+    pub fn maximal_cliques(&self) -> Vec<Vec<usize>> {
+        debug_assert!(!self.is_directed(), "cliques need an undirected graph");
+
+        let mut result = vec![];
+        self.bron_kerbosch(
+            &mut vec![],
+            BitSet::full(self.len()),
+            BitSet::new(),
+            &mut result,
+        );
+        result
+    }
+
+    /// The largest clique in the graph, with its nodes in ascending order. If
+    /// there are several of the same size, one of them is chosen arbitrarily.
+    pub fn maximum_clique(&self) -> Vec<usize> {
+        self.maximal_cliques()
+            .into_iter()
+            .max_by_key(|clique| clique.len())
+            .unwrap_or_default()
+    }
+
+    /// Every clique with exactly `k` nodes (not just the maximal ones). Each
+    /// clique is returned once, with its nodes in ascending order.
+    pub fn cliques_of_size(&self, k: usize) -> Vec<Vec<usize>> {
+        debug_assert!(!self.is_directed(), "cliques need an undirected graph");
+
+        let mut result = vec![];
+        self.extend_clique(&mut vec![], BitSet::full(self.len()), k, &mut result);
+        result
+    }
+
+    fn bron_kerbosch(
+        &self,
+        r: &mut Vec<usize>,
+        mut p: BitSet,
+        mut x: BitSet,
+        result: &mut Vec<Vec<usize>>,
+    ) {
+        if p.is_empty() && x.is_empty() {
+            let mut clique = r.clone();
+            clique.sort();
+            result.push(clique);
+            return;
+        }
+
+        // Any maximal clique must contain either the pivot or one of its
+        // non-neighbours, so picking the pivot with the most neighbours in `p`
+        // leaves the fewest branches to explore.
+        let pivot = p
+            .union(&x)
+            .iter()
+            .max_by_key(|&u| p.intersection_len(self.adjacency(u)))
+            .unwrap();
+
+        for v in p.difference(self.adjacency(pivot)).iter() {
+            let neighbours = self.adjacency(v);
+
+            r.push(v);
+            self.bron_kerbosch(
+                r,
+                p.intersection(neighbours),
+                x.intersection(neighbours),
+                result,
+            );
+            r.pop();
+
+            p.remove(v);
+            x.insert(v);
+        }
+    }
+
+    fn extend_clique(
+        &self,
+        clique: &mut Vec<usize>,
+        candidates: BitSet,
+        k: usize,
+        result: &mut Vec<Vec<usize>>,
+    ) {
+        if clique.len() == k {
+            result.push(clique.clone());
+            return;
+        }
+
+        let mut remaining = candidates.clone();
+        for v in candidates.iter() {
+            remaining.remove(v);
+            if clique.len() + 1 + remaining.len() < k {
+                break;
+            }
+
+            clique.push(v);
+            self.extend_clique(clique, remaining.intersection(self.adjacency(v)), k, result);
+            clique.pop();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_cliques() {
+        // a 4-clique 0-1-2-3 with a triangle 2-3-4 hanging off one edge
+        let mut graph = Graph::new_undirected();
+        for (a, b) in [
+            (0, 1),
+            (0, 2),
+            (0, 3),
+            (1, 2),
+            (1, 3),
+            (2, 3),
+            (2, 4),
+            (3, 4),
+        ] {
+            graph.add_named_edge(&a.to_string(), &b.to_string());
+        }
+
+        let mut maximal = graph.maximal_cliques();
+        maximal.sort();
+        assert_eq!(maximal, vec![vec![0, 1, 2, 3], vec![2, 3, 4]]);
+
+        assert_eq!(graph.maximum_clique(), vec![0, 1, 2, 3]);
+
+        assert_eq!(graph.cliques_of_size(3).len(), 5);
+        assert_eq!(graph.cliques_of_size(4), vec![vec![0, 1, 2, 3]]);
+        assert!(graph.cliques_of_size(5).is_empty());
+    }
+}
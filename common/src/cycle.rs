@@ -0,0 +1,163 @@
+use std::{collections::HashMap, hash::Hash};
+
+/// The shape of a sequence `x0, f(x0), f(f(x0)), ...` which eventually repeats.
+///
+/// The states at steps `prefix_length..prefix_length + cycle_length` repeat forever.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Cycle {
+    pub prefix_length: usize,
+    pub cycle_length: usize,
+}
+
+impl Cycle {
+    /// Finds the cycle using Brent's algorithm, which only keeps two states
+    /// around at once but calls `step` a few times more than strictly needed.
+    ///
+    /// Never returns if the sequence doesn't repeat.
+    pub fn brent<T: Clone + PartialEq>(start: &T, mut step: impl FnMut(&T) -> T) -> Self {
+        // find the cycle length by racing a hare ahead of a tortoise which
+        // teleports to the hare at every power of 2
+        let mut power = 1;
+        let mut cycle_length = 1;
+        let mut tortoise = start.clone();
+        let mut hare = step(start);
+
+        while tortoise != hare {
+            if power == cycle_length {
+                tortoise = hare.clone();
+                power *= 2;
+                cycle_length = 0;
+            }
+
+            hare = step(&hare);
+            cycle_length += 1;
+        }
+
+        // then start a hare `cycle_length` ahead of the tortoise, so they meet
+        // at the start of the cycle
+        let mut tortoise = start.clone();
+        let mut hare = start.clone();
+        for _ in 0..cycle_length {
+            hare = step(&hare);
+        }
+
+        let mut prefix_length = 0;
+        while tortoise != hare {
+            tortoise = step(&tortoise);
+            hare = step(&hare);
+            prefix_length += 1;
+        }
+
+        Self {
+            prefix_length,
+            cycle_length,
+        }
+    }
+
+    /// The earliest step which has the same state as step `n`
+    pub fn equivalent_step(&self, n: usize) -> usize {
+        if n < self.prefix_length {
+            n
+        } else {
+            self.prefix_length + (n - self.prefix_length) % self.cycle_length
+        }
+    }
+
+    /// Calculates the state after `n` steps, without performing all of them.
+    pub fn state_at<T: Clone>(&self, start: &T, mut step: impl FnMut(&T) -> T, n: usize) -> T {
+        let mut state = start.clone();
+        for _ in 0..self.equivalent_step(n) {
+            state = step(&state);
+        }
+
+        state
+    }
+}
+
+/// Every state of a repeating sequence up to the point where it first repeats,
+/// which lets you jump to any step directly.
+#[derive(Clone, Debug)]
+pub struct CycleHistory<T> {
+    pub cycle: Cycle,
+    states: Vec<T>,
+}
+
+impl<T: Clone + Hash + Eq> CycleHistory<T> {
+    /// Steps the sequence, remembering every state in a hash map until one
+    /// repeats. This calls `step` exactly once per state but needs memory for
+    /// all of them.
+    ///
+    /// Never returns if the sequence doesn't repeat.
+    pub fn find(start: T, mut step: impl FnMut(&T) -> T) -> Self {
+        let mut seen = HashMap::new();
+        let mut states = vec![];
+        let mut current = start;
+
+        let prefix_length = loop {
+            if let Some(&first_seen) = seen.get(&current) {
+                break first_seen;
+            }
+
+            seen.insert(current.clone(), states.len());
+            let next = step(&current);
+            states.push(current);
+            current = next;
+        };
+
+        Self {
+            cycle: Cycle {
+                prefix_length,
+                cycle_length: states.len() - prefix_length,
+            },
+            states,
+        }
+    }
+}
+
+impl<T> CycleHistory<T> {
+    /// The state after `n` steps
+    pub fn state_at(&self, n: usize) -> &T {
+        &self.states[self.cycle.equivalent_step(n)]
+    }
+
+    /// Every state before the sequence repeats, starting with the initial state
+    pub fn states(&self) -> &[T] {
+        &self.states
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 2, 4, 16, 256 % 100 = 56, 36, 96, 16, ...
+    fn square_mod_100(x: &u32) -> u32 {
+        x * x % 100
+    }
+
+    #[test]
+    fn brent_finds_cycle() {
+        let cycle = Cycle::brent(&2, square_mod_100);
+
+        assert_eq!(
+            cycle,
+            Cycle {
+                prefix_length: 2,
+                cycle_length: 4
+            }
+        );
+        assert_eq!(cycle.equivalent_step(1), 1);
+        assert_eq!(cycle.equivalent_step(1_000_000_000), 4);
+        assert_eq!(cycle.state_at(&2, square_mod_100, 7), 56);
+    }
+
+    #[test]
+    fn hashed_matches_brent() {
+        let history = CycleHistory::find(2, square_mod_100);
+
+        assert_eq!(history.cycle, Cycle::brent(&2, square_mod_100));
+        assert_eq!(history.states(), &[2, 4, 16, 56, 36, 96]);
+        assert_eq!(*history.state_at(7), 56);
+        assert_eq!(*history.state_at(1_000_000_001), 96);
+    }
+}
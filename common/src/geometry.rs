@@ -0,0 +1,215 @@
+use std::array;
+
+use crate::{Matrix, Rational};
+
+/// A line in `N` dimensions through `origin` going in `direction`, with the
+/// point at time `t` being `origin + t * direction`.
+///
+/// The same type also describes a ray (`t >= 0`) or a segment from `origin` to
+/// `origin + direction` (`0 <= t <= 1`), depending on which intersection
+/// method is used. Everything is calculated with exact rationals, so huge
+/// coordinates don't lose precision the way they would with floats.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Line<const N: usize> {
+    pub origin: [i128; N],
+    pub direction: [i128; N],
+}
+
+pub type Line2 = Line<2>;
+pub type Line3 = Line<3>;
+
+/// How two lines meet
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Intersection {
+    /// The lines cross at a single point, which is at time `t` on the first
+    /// line and time `u` on the second
+    Point { t: Rational, u: Rational },
+    /// The lines point in the same direction but never meet
+    Parallel,
+    /// The lines are the same line, possibly travelling at different speeds
+    Colinear,
+    /// The lines aren't parallel but never meet, which can only happen in 3 or
+    /// more dimensions
+    Skew,
+}
+
+impl<const N: usize> Line<N> {
+    pub fn new(origin: [i128; N], direction: [i128; N]) -> Self {
+        assert!(
+            direction.iter().any(|&d| d != 0),
+            "Line must have a non-zero direction"
+        );
+
+        Self { origin, direction }
+    }
+
+    /// The segment from `start` to `end`, so that `t = 0` is `start` and
+    /// `t = 1` is `end`
+    pub fn segment(start: [i128; N], end: [i128; N]) -> Self {
+        Self::new(start, array::from_fn(|i| end[i] - start[i]))
+    }
+
+    pub fn at(&self, t: Rational) -> [Rational; N] {
+        array::from_fn(|i| {
+            Rational::integer(self.origin[i]) + t * Rational::integer(self.direction[i])
+        })
+    }
+
+    /// Keeps only the given axes, for example `[0, 1]` to project onto the xy
+    /// plane. Returns `None` if the line doesn't move along any of those axes,
+    /// since it projects to a single point rather than a line.
+    pub fn project<const M: usize>(&self, axes: [usize; M]) -> Option<Line<M>> {
+        let direction = axes.map(|axis| self.direction[axis]);
+        if direction.iter().all(|&d| d == 0) {
+            return None;
+        }
+
+        Some(Line {
+            origin: axes.map(|axis| self.origin[axis]),
+            direction,
+        })
+    }
+
+    pub fn is_parallel(&self, other: &Self) -> bool {
+        Matrix::from_rows((0..N).map(|i| vec![self.direction[i], other.direction[i]])).rank() < 2
+    }
+
+    pub fn intersect(&self, other: &Self) -> Intersection {
+        let offset: [i128; N] = array::from_fn(|i| other.origin[i] - self.origin[i]);
+
+        if self.is_parallel(other) {
+            // the same line iff the offset between the origins is along the line too
+            let along = Matrix::from_rows((0..N).map(|i| vec![self.direction[i], offset[i]]));
+            return if along.rank() < 2 {
+                Intersection::Colinear
+            } else {
+                Intersection::Parallel
+            };
+        }
+
+        // origin + t * direction = other.origin + u * other.direction
+        let matrix =
+            Matrix::from_rows((0..N).map(|i| vec![self.direction[i], -other.direction[i]]));
+        let rhs = offset.map(Rational::integer);
+
+        match matrix.solve(&rhs) {
+            Some(times) => Intersection::Point {
+                t: times[0],
+                u: times[1],
+            },
+            None => Intersection::Skew,
+        }
+    }
+
+    /// The times at which two rays cross, if they cross at a single point.
+    /// Colinear rays are never reported as crossing.
+    pub fn ray_intersection(&self, other: &Self) -> Option<(Rational, Rational)> {
+        match self.intersect(other) {
+            Intersection::Point { t, u } if t >= Rational::ZERO && u >= Rational::ZERO => {
+                Some((t, u))
+            }
+            _ => None,
+        }
+    }
+
+    /// The times at which two segments cross, if they cross at a single point.
+    /// Colinear segments are never reported as crossing.
+    pub fn segment_intersection(&self, other: &Self) -> Option<(Rational, Rational)> {
+        let within = |time: Rational| Rational::ZERO <= time && time <= Rational::ONE;
+
+        match self.intersect(other) {
+            Intersection::Point { t, u } if within(t) && within(u) => Some((t, u)),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hailstones_2d() {
+        // from the 2023 day 24 example
+        let a = Line::new([19, 13, 30], [-2, 1, -2])
+            .project([0, 1])
+            .unwrap();
+        let b = Line::new([18, 19, 22], [-1, -1, -2])
+            .project([0, 1])
+            .unwrap();
+        let c = Line::new([20, 25, 34], [-2, -2, -4])
+            .project([0, 1])
+            .unwrap();
+
+        let Intersection::Point { t, u } = a.intersect(&b) else {
+            panic!("Expected a and b to cross");
+        };
+        assert_eq!(a.at(t), b.at(u));
+        assert_eq!(a.at(t), [Rational::new(43, 3), Rational::new(46, 3)]);
+
+        assert_eq!(b.intersect(&c), Intersection::Parallel);
+        assert_eq!(b.intersect(&b), Intersection::Colinear);
+
+        let d = Line2::new([20, 19], [1, -5]);
+        assert!(matches!(a.intersect(&d), Intersection::Point { .. }));
+        assert_eq!(a.ray_intersection(&d), None);
+
+        let vertical = Line3::new([1, 2, 3], [0, 0, 4]);
+        assert_eq!(vertical.project([0, 1]), None);
+        assert_eq!(vertical.project([2, 0]), Some(Line2::new([3, 1], [4, 0])));
+    }
+
+    #[test]
+    fn lines_3d() {
+        let a = Line3::new([0, 0, 0], [1, 1, 1]);
+        let b = Line3::new([2, 0, 2], [0, 1, 0]);
+        assert_eq!(
+            a.intersect(&b),
+            Intersection::Point {
+                t: Rational::integer(2),
+                u: Rational::integer(2),
+            }
+        );
+
+        let skew = Line3::new([0, 0, 5], [1, 0, 0]);
+        assert_eq!(a.intersect(&skew), Intersection::Skew);
+
+        let colinear = Line3::new([-3, -3, -3], [2, 2, 2]);
+        assert_eq!(a.intersect(&colinear), Intersection::Colinear);
+    }
+
+    #[test]
+    fn segments() {
+        let a = Line2::segment([0, 0], [4, 4]);
+        let b = Line2::segment([0, 4], [4, 0]);
+        let c = Line2::segment([0, 4], [1, 3]);
+
+        let half = Rational::new(1, 2);
+        assert_eq!(a.segment_intersection(&b), Some((half, half)));
+        assert_eq!(a.segment_intersection(&c), None);
+        assert_eq!(a.ray_intersection(&c), Some((half, Rational::integer(2))));
+    }
+
+    #[test]
+    fn huge_coordinates() {
+        let a = Line3::new(
+            [
+                200_000_000_000_001,
+                300_000_000_000_002,
+                400_000_000_000_003,
+            ],
+            [-13, 7, 11],
+        );
+        let b = Line3::new(
+            a.at(Rational::integer(1_000_000_007))
+                .map(|v| v.to_integer().unwrap()),
+            [5, -3, 1],
+        );
+
+        let Intersection::Point { t, u } = a.intersect(&b) else {
+            panic!("Expected the lines to cross");
+        };
+        assert_eq!(t, Rational::integer(1_000_000_007));
+        assert_eq!(u, Rational::ZERO);
+    }
+}
@@ -0,0 +1,228 @@
+use std::{collections::HashMap, ops::Range};
+
+use crate::BitSet;
+
+/// A graph whose nodes are named by strings from the puzzle input.
+///
+/// Names are interned to indices in `0..len()` in the order they are first
+/// seen, and each node's neighbours are stored as a [`BitSet`] row so that
+/// neighbourhood intersections are cheap.
+#[derive(Clone, Debug)]
+pub struct Graph {
+    names: Vec<String>,
+    lookup: HashMap<String, usize>,
+    adjacency: Vec<BitSet>,
+    directed: bool,
+}
+
+impl Graph {
+    pub fn new_undirected() -> Self {
+        Self {
+            names: vec![],
+            lookup: HashMap::new(),
+            adjacency: vec![],
+            directed: false,
+        }
+    }
+
+    pub fn new_directed() -> Self {
+        Self {
+            directed: true,
+            ..Self::new_undirected()
+        }
+    }
+
+    /// Parses an undirected graph with one `a-b` edge per line
+    pub fn parse_pairs(input: &str) -> Self {
+        let mut graph = Self::new_undirected();
+
+        for line in input.lines() {
+            let (left, right) = line.split_once('-').unwrap();
+            graph.add_named_edge(left, right);
+        }
+
+        graph
+    }
+
+    /// Parses an undirected graph with lines of the form `a: b c d`
+    pub fn parse_lists(input: &str) -> Self {
+        let mut graph = Self::new_undirected();
+
+        for line in input.lines() {
+            let (start, connections) = line.split_once(':').unwrap();
+            let start = graph.intern(start.trim());
+
+            for connection in connections.split_whitespace() {
+                let connection = graph.intern(connection);
+                graph.add_edge(start, connection);
+            }
+        }
+
+        graph
+    }
+
+    /// Parses a directed graph with lines of the form `a -> b, c`
+    pub fn parse_arrows(input: &str) -> Self {
+        let mut graph = Self::new_directed();
+
+        for line in input.lines() {
+            let (start, connections) = line.split_once("->").unwrap();
+            let start = graph.intern(start.trim());
+
+            for connection in connections.split(',') {
+                let connection = graph.intern(connection.trim());
+                graph.add_edge(start, connection);
+            }
+        }
+
+        graph
+    }
+
+    pub fn is_directed(&self) -> bool {
+        self.directed
+    }
+
+    /// Returns the index for `name`, adding a new node if it hasn't been seen before
+    pub fn intern(&mut self, name: &str) -> usize {
+        if let Some(&index) = self.lookup.get(name) {
+            return index;
+        }
+
+        let index = self.names.len();
+        self.names.push(name.to_string());
+        self.lookup.insert(name.to_string(), index);
+        self.adjacency.push(BitSet::new());
+        index
+    }
+
+    /// Adds a new node without a name, for graphs whose nodes are already
+    /// numbered. Its name is empty and it can't be found with [`Graph::index_of`].
+    pub fn add_node(&mut self) -> usize {
+        let index = self.names.len();
+        self.names.push(String::new());
+        self.adjacency.push(BitSet::new());
+        index
+    }
+
+    pub fn index_of(&self, name: &str) -> Option<usize> {
+        self.lookup.get(name).copied()
+    }
+
+    pub fn name(&self, index: usize) -> &str {
+        &self.names[index]
+    }
+
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    pub fn nodes(&self) -> Range<usize> {
+        0..self.len()
+    }
+
+    /// Adds an edge between two existing nodes. For undirected graphs the
+    /// reverse edge is added too.
+    pub fn add_edge(&mut self, from: usize, to: usize) {
+        self.adjacency[from].insert(to);
+        if !self.directed {
+            self.adjacency[to].insert(from);
+        }
+    }
+
+    pub fn add_named_edge(&mut self, from: &str, to: &str) {
+        let from = self.intern(from);
+        let to = self.intern(to);
+        self.add_edge(from, to);
+    }
+
+    pub fn remove_edge(&mut self, from: usize, to: usize) {
+        self.adjacency[from].remove(to);
+        if !self.directed {
+            self.adjacency[to].remove(from);
+        }
+    }
+
+    pub fn has_edge(&self, from: usize, to: usize) -> bool {
+        self.adjacency[from].contains(to)
+    }
+
+    pub fn adjacency(&self, index: usize) -> &BitSet {
+        &self.adjacency[index]
+    }
+
+    pub fn neighbours(&self, index: usize) -> impl Iterator<Item = usize> + '_ {
+        self.adjacency[index].iter()
+    }
+
+    pub fn degree(&self, index: usize) -> usize {
+        self.adjacency[index].len()
+    }
+
+    /// Every edge in the graph. Undirected edges are only returned once, with
+    /// the smaller index first.
+    pub fn edges(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.nodes().flat_map(move |from| {
+            self.neighbours(from)
+                .filter(move |&to| self.directed || from < to)
+                .map(move |to| (from, to))
+        })
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.edges().count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_pairs_interns_names() {
+        let graph = Graph::parse_pairs("kh-tc\nqp-kh\ntc-qp");
+
+        assert_eq!(graph.len(), 3);
+        assert_eq!(graph.index_of("qp"), Some(2));
+        assert_eq!(graph.name(1), "tc");
+
+        let kh = graph.index_of("kh").unwrap();
+        assert_eq!(graph.neighbours(kh).collect::<Vec<_>>(), &[1, 2]);
+        assert_eq!(graph.edges().collect::<Vec<_>>(), &[(0, 1), (0, 2), (1, 2)]);
+    }
+
+    #[test]
+    fn parse_lists_and_arrows() {
+        let graph = Graph::parse_lists("jqt: rhn xhk\nrhn: xhk");
+        assert!(!graph.is_directed());
+        assert_eq!(graph.edge_count(), 3);
+        assert!(graph.has_edge(2, 0));
+
+        let graph = Graph::parse_arrows("broadcaster -> a, b\na -> b");
+        assert!(graph.is_directed());
+        assert_eq!(graph.edge_count(), 3);
+        assert!(graph.has_edge(1, 2));
+        assert!(!graph.has_edge(2, 1));
+    }
+
+    #[test]
+    fn numbered_nodes() {
+        let mut graph = Graph::new_directed();
+        let nodes: Vec<_> = (0..3).map(|_| graph.add_node()).collect();
+        assert_eq!(nodes, &[0, 1, 2]);
+
+        graph.add_edge(2, 0);
+        assert_eq!(graph.len(), 3);
+        assert!(graph.has_edge(2, 0));
+        assert_eq!(graph.index_of(""), None);
+
+        assert_eq!(graph.intern("named"), 3);
+    }
+}
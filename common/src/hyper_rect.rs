@@ -0,0 +1,126 @@
+use std::{array, ops::Range};
+
+use crate::RangeValue;
+
+/// An axis-aligned box in `N` dimensions, with a half-open range of values
+/// along each axis.
+///
+/// This is useful for propagating a whole block of possible inputs through a
+/// series of threshold checks, splitting it in two at each one.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct HyperRect<T, const N: usize> {
+    pub axes: [Range<T>; N],
+}
+
+impl<T: RangeValue, const N: usize> HyperRect<T, N> {
+    pub fn new(axes: [Range<T>; N]) -> Self {
+        Self { axes }
+    }
+
+    /// A box with the same range along every axis
+    pub fn cube(range: Range<T>) -> Self {
+        Self {
+            axes: array::from_fn(|_| range.clone()),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.axes.iter().any(|axis| axis.is_empty())
+    }
+
+    /// The number of points inside the box
+    pub fn volume(&self) -> T {
+        if self.is_empty() {
+            return T::ZERO;
+        }
+
+        self.axes
+            .iter()
+            .fold(T::ONE, |volume, axis| volume * (axis.end - axis.start))
+    }
+
+    pub fn contains(&self, point: &[T; N]) -> bool {
+        self.axes
+            .iter()
+            .zip(point)
+            .all(|(axis, value)| axis.contains(value))
+    }
+
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        let result = Self {
+            axes: array::from_fn(|i| {
+                self.axes[i].start.max(other.axes[i].start)..self.axes[i].end.min(other.axes[i].end)
+            }),
+        };
+
+        (!result.is_empty()).then_some(result)
+    }
+
+    /// Splits along `axis` into the part with values less than `at` and the
+    /// part with values greater than or equal to it. Either part is `None` if
+    /// it would be empty.
+    pub fn split_at(&self, axis: usize, at: T) -> (Option<Self>, Option<Self>) {
+        let range = &self.axes[axis];
+        let at = at.clamp(range.start, range.end);
+
+        let mut below = self.clone();
+        below.axes[axis].end = at;
+
+        let mut above = self.clone();
+        above.axes[axis].start = at;
+
+        (
+            (!below.is_empty()).then_some(below),
+            (!above.is_empty()).then_some(above),
+        )
+    }
+
+    /// Splits into the part where the value along `axis` is less than
+    /// `threshold`, and the rest.
+    pub fn split_less_than(&self, axis: usize, threshold: T) -> (Option<Self>, Option<Self>) {
+        self.split_at(axis, threshold)
+    }
+
+    /// Splits into the part where the value along `axis` is greater than
+    /// `threshold`, and the rest.
+    pub fn split_greater_than(&self, axis: usize, threshold: T) -> (Option<Self>, Option<Self>) {
+        let (rest, greater) = self.split_at(axis, threshold + T::ONE);
+        (greater, rest)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::single_range_in_vec_init)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn volume_and_contains() {
+        let cube = HyperRect::<u64, 4>::cube(1..4001);
+        assert_eq!(cube.volume(), 4000u64.pow(4));
+        assert!(cube.contains(&[1, 4000, 2, 3]));
+        assert!(!cube.contains(&[0, 1, 1, 1]));
+
+        let a = HyperRect::new([0..10, 0..10]);
+        let b = HyperRect::new([5..15, 8..20]);
+        assert_eq!(a.intersection(&b), Some(HyperRect::new([5..10, 8..10])));
+        assert_eq!(a.intersection(&HyperRect::new([10..20, 0..10])), None);
+    }
+
+    #[test]
+    fn split_on_thresholds() {
+        let rect = HyperRect::new([1..4001, 1..4001]);
+
+        let (pass, fail) = rect.split_less_than(0, 1351);
+        assert_eq!(pass, Some(HyperRect::new([1..1351, 1..4001])));
+        assert_eq!(fail, Some(HyperRect::new([1351..4001, 1..4001])));
+
+        let (pass, fail) = rect.split_greater_than(1, 2090);
+        assert_eq!(pass.unwrap().volume(), 4000 * 1910);
+        assert_eq!(fail.unwrap().volume(), 4000 * 2090);
+
+        let (pass, fail) = rect.split_greater_than(1, 5000);
+        assert_eq!(pass, None);
+        assert_eq!(fail, Some(rect));
+    }
+}
@@ -0,0 +1,34 @@
+mod automaton;
+mod bitset;
+mod checked;
+mod clique;
+mod cycle;
+mod geometry;
+mod graph;
+mod hyper_rect;
+mod linear;
+mod memo;
+mod min_cut;
+mod modular;
+mod polygon;
+mod range_set;
+mod recorder;
+mod topo;
+mod union_find;
+
+pub use automaton::*;
+pub use bitset::*;
+pub use checked::*;
+pub use cycle::*;
+pub use geometry::*;
+pub use graph::*;
+pub use hyper_rect::*;
+pub use linear::*;
+pub use memo::*;
+pub use min_cut::*;
+pub use modular::*;
+pub use polygon::*;
+pub use range_set::*;
+pub use recorder::*;
+pub use topo::*;
+pub use union_find::*;
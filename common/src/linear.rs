@@ -0,0 +1,386 @@
+use std::{
+    cmp::Ordering,
+    fmt::{self, Display},
+    ops::{Add, Div, Mul, Neg, Sub},
+};
+
+/// An exact fraction, always stored in lowest terms with a positive denominator.
+///
+/// Arithmetic panics on overflow rather than silently wrapping, even in release
+/// builds.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Rational {
+    numerator: i128,
+    denominator: i128,
+}
+
+impl Rational {
+    pub const ZERO: Self = Self::integer(0);
+    pub const ONE: Self = Self::integer(1);
+
+    pub fn new(numerator: i128, denominator: i128) -> Self {
+        assert!(denominator != 0, "Rational with zero denominator");
+
+        let divisor = gcd_i128(numerator, denominator) * denominator.signum();
+        Self {
+            numerator: numerator / divisor,
+            denominator: denominator / divisor,
+        }
+    }
+
+    pub const fn integer(value: i128) -> Self {
+        Self {
+            numerator: value,
+            denominator: 1,
+        }
+    }
+
+    pub fn numerator(self) -> i128 {
+        self.numerator
+    }
+
+    pub fn denominator(self) -> i128 {
+        self.denominator
+    }
+
+    pub fn is_zero(self) -> bool {
+        self.numerator == 0
+    }
+
+    pub fn is_integer(self) -> bool {
+        self.denominator == 1
+    }
+
+    /// The value as an integer, or `None` if it has a fractional part
+    pub fn to_integer(self) -> Option<i128> {
+        self.is_integer().then_some(self.numerator)
+    }
+
+    pub fn abs(self) -> Self {
+        Self {
+            numerator: self.numerator.abs(),
+            denominator: self.denominator,
+        }
+    }
+
+    pub fn recip(self) -> Self {
+        Self::new(self.denominator, self.numerator)
+    }
+}
+
+fn gcd_i128(mut a: i128, mut b: i128) -> i128 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+
+    a.abs()
+}
+
+fn checked<T>(value: Option<T>) -> T {
+    value.expect("Rational arithmetic overflowed i128")
+}
+
+impl From<i128> for Rational {
+    fn from(value: i128) -> Self {
+        Self::integer(value)
+    }
+}
+
+impl From<i64> for Rational {
+    fn from(value: i64) -> Self {
+        Self::integer(value.into())
+    }
+}
+
+impl Add for Rational {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        // dividing by the gcd of the denominators first keeps the intermediate values small
+        let g = gcd_i128(self.denominator, rhs.denominator);
+        let lhs_scale = rhs.denominator / g;
+        let rhs_scale = self.denominator / g;
+
+        Self::new(
+            checked(
+                checked(self.numerator.checked_mul(lhs_scale))
+                    .checked_add(checked(rhs.numerator.checked_mul(rhs_scale))),
+            ),
+            checked(self.denominator.checked_mul(lhs_scale)),
+        )
+    }
+}
+
+impl Neg for Rational {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self {
+            numerator: -self.numerator,
+            denominator: self.denominator,
+        }
+    }
+}
+
+impl Sub for Rational {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        self + -rhs
+    }
+}
+
+impl Mul for Rational {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        // cross-cancel before multiplying so that we only overflow if the result doesn't fit
+        let g1 = gcd_i128(self.numerator, rhs.denominator).max(1);
+        let g2 = gcd_i128(rhs.numerator, self.denominator).max(1);
+
+        Self::new(
+            checked((self.numerator / g1).checked_mul(rhs.numerator / g2)),
+            checked((self.denominator / g2).checked_mul(rhs.denominator / g1)),
+        )
+    }
+}
+
+impl Div for Rational {
+    type Output = Self;
+
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, rhs: Self) -> Self {
+        self * rhs.recip()
+    }
+}
+
+impl PartialOrd for Rational {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Rational {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (*self - *other).numerator.cmp(&0)
+    }
+}
+
+impl Display for Rational {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_integer() {
+            write!(f, "{}", self.numerator)
+        } else {
+            write!(f, "{}/{}", self.numerator, self.denominator)
+        }
+    }
+}
+
+/// A dense matrix of exact rationals
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Matrix {
+    rows: usize,
+    cols: usize,
+    values: Vec<Rational>,
+}
+
+impl Matrix {
+    pub fn from_rows<V: Into<Rational>>(rows: impl IntoIterator<Item = Vec<V>>) -> Self {
+        let mut values = vec![];
+        let mut row_count = 0;
+        let mut cols = None;
+
+        for row in rows {
+            assert_eq!(
+                *cols.get_or_insert(row.len()),
+                row.len(),
+                "All rows must be the same width"
+            );
+
+            values.extend(row.into_iter().map(Into::into));
+            row_count += 1;
+        }
+
+        Self {
+            rows: row_count,
+            cols: cols.unwrap_or(0),
+            values,
+        }
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> Rational {
+        self.values[row * self.cols + col]
+    }
+
+    fn row_mut(&mut self, row: usize) -> &mut [Rational] {
+        &mut self.values[row * self.cols..(row + 1) * self.cols]
+    }
+
+    fn swap_rows(&mut self, a: usize, b: usize) {
+        for col in 0..self.cols {
+            self.values.swap(a * self.cols + col, b * self.cols + col);
+        }
+    }
+
+    /// Subtracts `factor` times row `source` from row `target`
+    fn eliminate(&mut self, target: usize, source: usize, factor: Rational) {
+        for col in 0..self.cols {
+            let value = self.get(source, col);
+            self.values[target * self.cols + col] = self.get(target, col) - factor * value;
+        }
+    }
+
+    /// Reduces the matrix to reduced row echelon form in place, returning the
+    /// column of the pivot in each non-zero row.
+    fn reduce(&mut self) -> Vec<usize> {
+        let mut pivots = vec![];
+
+        for col in 0..self.cols {
+            let row = pivots.len();
+            let Some(pivot_row) = (row..self.rows).find(|&r| !self.get(r, col).is_zero()) else {
+                continue;
+            };
+
+            self.swap_rows(row, pivot_row);
+
+            let pivot = self.get(row, col);
+            for value in self.row_mut(row) {
+                *value = *value / pivot;
+            }
+
+            for other in 0..self.rows {
+                let factor = self.get(other, col);
+                if other != row && !factor.is_zero() {
+                    self.eliminate(other, row, factor);
+                }
+            }
+
+            pivots.push(col);
+        }
+
+        pivots
+    }
+
+    pub fn rank(&self) -> usize {
+        self.clone().reduce().len()
+    }
+
+    pub fn determinant(&self) -> Rational {
+        assert_eq!(self.rows, self.cols, "Determinant needs a square matrix");
+
+        let mut working = self.clone();
+        let mut determinant = Rational::ONE;
+
+        for col in 0..self.cols {
+            let Some(pivot_row) = (col..self.rows).find(|&r| !working.get(r, col).is_zero()) else {
+                return Rational::ZERO;
+            };
+
+            if pivot_row != col {
+                working.swap_rows(col, pivot_row);
+                determinant = -determinant;
+            }
+
+            let pivot = working.get(col, col);
+            determinant = determinant * pivot;
+
+            for row in col + 1..self.rows {
+                let factor = working.get(row, col) / pivot;
+                if !factor.is_zero() {
+                    working.eliminate(row, col, factor);
+                }
+            }
+        }
+
+        determinant
+    }
+
+    /// Solves `self * x = rhs` using Gaussian elimination. Returns `None` if
+    /// there is no solution or if there are infinitely many.
+    ///
+    /// There may be more equations than unknowns, as long as they're consistent.
+    pub fn solve(&self, rhs: &[Rational]) -> Option<Vec<Rational>> {
+        assert_eq!(rhs.len(), self.rows, "Need one right hand side per row");
+
+        let mut augmented = Matrix::from_rows((0..self.rows).map(|row| {
+            let mut values: Vec<_> = (0..self.cols).map(|col| self.get(row, col)).collect();
+            values.push(rhs[row]);
+            values
+        }));
+
+        let pivots = augmented.reduce();
+        if pivots.last() == Some(&self.cols) || pivots.len() < self.cols {
+            return None;
+        }
+
+        Some(
+            (0..self.cols)
+                .map(|row| augmented.get(row, self.cols))
+                .collect(),
+        )
+    }
+
+    /// Like [`Matrix::solve`], but only returns solutions where every value is an integer
+    pub fn solve_integer(&self, rhs: &[i128]) -> Option<Vec<i128>> {
+        let rhs: Vec<_> = rhs.iter().map(|&value| Rational::integer(value)).collect();
+
+        self.solve(&rhs)?
+            .into_iter()
+            .map(Rational::to_integer)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rational_arithmetic() {
+        let half = Rational::new(2, 4);
+        let third = Rational::new(-1, -3);
+
+        assert_eq!(half.numerator(), 1);
+        assert_eq!(half.denominator(), 2);
+        assert_eq!(half + third, Rational::new(5, 6));
+        assert_eq!(half - third, Rational::new(1, 6));
+        assert_eq!(half * third, Rational::new(1, 6));
+        assert_eq!(half / third, Rational::new(3, 2));
+        assert_eq!(Rational::new(3, -6), -half);
+        assert!(third < half);
+        assert_eq!((half + half).to_integer(), Some(1));
+        assert_eq!(half.to_string(), "1/2");
+    }
+
+    #[test]
+    fn solves_systems() {
+        // the first claw machine from 2024 day 13
+        let matrix = Matrix::from_rows([vec![94i128, 22], vec![34, 67]]);
+        assert_eq!(matrix.determinant(), Rational::integer(94 * 67 - 22 * 34));
+        assert_eq!(matrix.solve_integer(&[8400, 5400]), Some(vec![80, 40]));
+        assert_eq!(matrix.solve_integer(&[8401, 5400]), None);
+
+        let singular = Matrix::from_rows([vec![1i128, 2], vec![2, 4]]);
+        assert_eq!(singular.determinant(), Rational::ZERO);
+        assert_eq!(singular.rank(), 1);
+        assert_eq!(singular.solve_integer(&[3, 6]), None);
+
+        let overdetermined = Matrix::from_rows([vec![1i128, 1], vec![1, -1], vec![2, 1]]);
+        assert_eq!(overdetermined.solve_integer(&[3, 1, 5]), Some(vec![2, 1]));
+        assert_eq!(overdetermined.solve_integer(&[3, 1, 6]), None);
+    }
+
+    #[test]
+    fn determinant_3x3() {
+        let matrix = Matrix::from_rows([vec![0i128, 2, 1], vec![1, 0, 3], vec![4, 1, 0]]);
+        assert_eq!(matrix.determinant(), Rational::integer(25));
+    }
+}
@@ -0,0 +1,123 @@
+use std::{collections::HashMap, fmt, hash::Hash};
+
+/// A cache of results for a recursive function, keyed by its arguments.
+///
+/// Rather than looking up and inserting into a `HashMap` by hand, a memoized
+/// function takes a `&mut Memo` and wraps its body in
+/// [`Memo::get_or_insert_with`], passing the memo along to any recursive calls:
+///
+/// ```
+/// # use aoc_common::Memo;
+/// fn fibonacci(n: u64, memo: &mut Memo<u64, u64>) -> u64 {
+///     if n < 2 {
+///         return n;
+///     }
+///
+///     memo.get_or_insert_with(n, |memo| fibonacci(n - 1, memo) + fibonacci(n - 2, memo))
+/// }
+///
+/// assert_eq!(fibonacci(90, &mut Memo::new()), 2_880_067_194_370_816_120);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Memo<K, V> {
+    cache: HashMap<K, V>,
+    hits: usize,
+    misses: usize,
+}
+
+/// How much use a [`Memo`] has been
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MemoStats {
+    pub entries: usize,
+    pub hits: usize,
+    pub misses: usize,
+}
+
+impl<K, V> Default for Memo<K, V> {
+    fn default() -> Self {
+        Self {
+            cache: HashMap::new(),
+            hits: 0,
+            misses: 0,
+        }
+    }
+}
+
+impl<K: Eq + Hash, V: Clone> Memo<K, V> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the cached value for `key`, or calculates it with `compute` and
+    /// caches the result. `compute` gets the memo back so that it can recurse.
+    pub fn get_or_insert_with(&mut self, key: K, compute: impl FnOnce(&mut Self) -> V) -> V {
+        if let Some(value) = self.cache.get(&key) {
+            self.hits += 1;
+            return value.clone();
+        }
+
+        self.misses += 1;
+        let value = compute(self);
+        self.cache.insert(key, value.clone());
+
+        value
+    }
+
+    pub fn get(&self, key: &K) -> Option<&V> {
+        self.cache.get(key)
+    }
+
+    pub fn len(&self) -> usize {
+        self.cache.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cache.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.cache.clear();
+    }
+
+    pub fn stats(&self) -> MemoStats {
+        MemoStats {
+            entries: self.cache.len(),
+            hits: self.hits,
+            misses: self.misses,
+        }
+    }
+}
+
+impl fmt::Display for MemoStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} entries, {} hits, {} misses",
+            self.entries, self.hits, self.misses
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn paths(x: usize, y: usize, memo: &mut Memo<(usize, usize), u64>) -> u64 {
+        if x == 0 || y == 0 {
+            return 1;
+        }
+
+        memo.get_or_insert_with((x, y), |memo| paths(x - 1, y, memo) + paths(x, y - 1, memo))
+    }
+
+    #[test]
+    fn lattice_paths() {
+        let mut memo = Memo::new();
+        assert_eq!(paths(16, 16, &mut memo), 601_080_390);
+
+        let stats = memo.stats();
+        assert_eq!(stats.entries, 16 * 16);
+        assert_eq!(stats.misses, 16 * 16);
+        assert_eq!(stats.hits, 15 * 15);
+    }
+}
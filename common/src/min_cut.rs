@@ -0,0 +1,251 @@
+use std::{
+    collections::{BinaryHeap, HashMap},
+    mem,
+};
+
+use crate::{BitSet, Graph};
+
+/// A cut of an undirected graph into two non-empty halves
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MinCut {
+    /// The edges crossing the cut, each with the node in `left` first
+    pub edges: Vec<(usize, usize)>,
+    pub left: Vec<usize>,
+    pub right: Vec<usize>,
+}
+
+impl Graph {
+    /// Finds a minimum cut deterministically using Stoer–Wagner. Returns `None`
+    /// if the graph has fewer than 2 nodes.
+    pub fn stoer_wagner_min_cut(&self) -> Option<MinCut> {
+        debug_assert!(!self.is_directed(), "min cut needs an undirected graph");
+
+        if self.len() < 2 {
+            return None;
+        }
+
+        // Each node starts as its own super-node, and the last node of each
+        // phase gets merged into the second to last one.
+        let mut weights: Vec<HashMap<usize, u64>> = vec![HashMap::new(); self.len()];
+        for (a, b) in self.edges() {
+            weights[a].insert(b, 1);
+            weights[b].insert(a, 1);
+        }
+
+        let mut members: Vec<Vec<usize>> = self.nodes().map(|node| vec![node]).collect();
+        let mut active: Vec<usize> = self.nodes().collect();
+        let mut best: Option<(u64, Vec<usize>)> = None;
+
+        while active.len() > 1 {
+            let mut key = vec![0; self.len()];
+            let mut added = vec![false; self.len()];
+            let mut order = vec![];
+
+            let mut queue = BinaryHeap::from([(0, active[0])]);
+            while let Some((weight, node)) = queue.pop() {
+                if added[node] || weight != key[node] {
+                    continue;
+                }
+
+                added[node] = true;
+                order.push(node);
+
+                for (&neighbour, &weight) in &weights[node] {
+                    if !added[neighbour] {
+                        key[neighbour] += weight;
+                        queue.push((key[neighbour], neighbour));
+                    }
+                }
+            }
+
+            if order.len() < active.len() {
+                // the graph is disconnected, so the nodes we reached form a cut of weight 0
+                let side = order.iter().flat_map(|&node| members[node].iter().copied());
+                return Some(self.cut_from_side(side.collect()));
+            }
+
+            let t = order[order.len() - 1];
+            let s = order[order.len() - 2];
+
+            if best.as_ref().is_none_or(|(weight, _)| key[t] < *weight) {
+                best = Some((key[t], members[t].clone()));
+            }
+
+            for (neighbour, weight) in mem::take(&mut weights[t]) {
+                weights[neighbour].remove(&t);
+                if neighbour != s {
+                    *weights[s].entry(neighbour).or_default() += weight;
+                    *weights[neighbour].entry(s).or_default() += weight;
+                }
+            }
+
+            let t_members = mem::take(&mut members[t]);
+            members[s].extend(t_members);
+            active.retain(|&node| node != t);
+        }
+
+        let (_, side) = best?;
+        Some(self.cut_from_side(side.into_iter().collect()))
+    }
+
+    /// Finds a cut using `trials` runs of Karger's random contraction, returning
+    /// the smallest one seen. The same seed always gives the same result, but
+    /// the cut is only minimal with high probability.
+    pub fn karger_min_cut(&self, seed: u64, trials: usize) -> Option<MinCut> {
+        debug_assert!(!self.is_directed(), "min cut needs an undirected graph");
+
+        if self.len() < 2 {
+            return None;
+        }
+
+        let mut rng = SplitMix64(seed);
+        let mut edges: Vec<_> = self.edges().collect();
+        let mut best: Option<MinCut> = None;
+
+        for _ in 0..trials {
+            // Contracting edges in a random order until 2 super-nodes remain
+            // is the same as building a random spanning forest with 2 trees.
+            rng.shuffle(&mut edges);
+
+            let mut parent: Vec<usize> = self.nodes().collect();
+            let mut components = self.len();
+
+            for &(a, b) in &edges {
+                if components == 2 {
+                    break;
+                }
+
+                let (root_a, root_b) = (find(&mut parent, a), find(&mut parent, b));
+                if root_a != root_b {
+                    parent[root_a] = root_b;
+                    components -= 1;
+                }
+            }
+
+            let root = find(&mut parent, 0);
+            let side = self
+                .nodes()
+                .filter(|&node| find(&mut parent, node) == root)
+                .collect();
+
+            let cut = self.cut_from_side(side);
+            if best
+                .as_ref()
+                .is_none_or(|best| cut.edges.len() < best.edges.len())
+            {
+                best = Some(cut);
+            }
+        }
+
+        best
+    }
+
+    fn cut_from_side(&self, side: BitSet) -> MinCut {
+        let left: Vec<_> = side.iter().collect();
+        let right: Vec<_> = self.nodes().filter(|&node| !side.contains(node)).collect();
+
+        let edges = left
+            .iter()
+            .flat_map(|&a| {
+                self.neighbours(a)
+                    .filter(|&b| !side.contains(b))
+                    .map(move |b| (a, b))
+            })
+            .collect();
+
+        MinCut { edges, left, right }
+    }
+}
+
+/// A small seedable random number generator, so that Karger's algorithm
+/// doesn't need a dependency for its shuffling
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Fisher–Yates shuffle. The modulo bias is negligible for slices much
+    /// shorter than 2^64.
+    fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            let j = (self.next() % (i as u64 + 1)) as usize;
+            items.swap(i, j);
+        }
+    }
+}
+
+fn find(parent: &mut [usize], node: usize) -> usize {
+    let mut root = node;
+    while parent[root] != root {
+        root = parent[root];
+    }
+
+    parent[node] = root;
+    root
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SNOWVERLOAD: &str = "jqt: rhn xhk nvd
+rsh: frs pzl lsr
+xhk: hfx
+cmg: qnr nvd lhk bvb
+rhn: xhk bvb hfx
+bvb: xhk hfx
+pzl: lsr hfx nvd
+qnr: nvd
+ntq: jqt hfx bvb xhk
+nvd: lhk
+lsr: lhk
+rzs: qnr cmg lsr rsh
+frs: qnr lhk lsr";
+
+    #[test]
+    fn stoer_wagner() {
+        let graph = Graph::parse_lists(SNOWVERLOAD);
+        let cut = graph.stoer_wagner_min_cut().unwrap();
+
+        assert_eq!(cut.edges.len(), 3);
+        assert_eq!(cut.left.len() * cut.right.len(), 54);
+
+        let mut edge_names: Vec<_> = cut
+            .edges
+            .iter()
+            .map(|&(a, b)| {
+                let mut names = [graph.name(a), graph.name(b)];
+                names.sort();
+                names
+            })
+            .collect();
+        edge_names.sort();
+        assert_eq!(edge_names, [["bvb", "cmg"], ["hfx", "pzl"], ["jqt", "nvd"]]);
+    }
+
+    #[test]
+    fn karger() {
+        let graph = Graph::parse_lists(SNOWVERLOAD);
+        let cut = graph.karger_min_cut(25, 100).unwrap();
+
+        assert_eq!(cut.edges.len(), 3);
+        assert_eq!(cut.left.len() * cut.right.len(), 54);
+        assert_eq!(graph.karger_min_cut(25, 100), Some(cut));
+    }
+
+    #[test]
+    fn disconnected_graph_has_empty_cut() {
+        let graph = Graph::parse_pairs("a-b\nc-d");
+        let cut = graph.stoer_wagner_min_cut().unwrap();
+
+        assert!(cut.edges.is_empty());
+        assert_eq!(cut.left.len(), 2);
+    }
+}
@@ -0,0 +1,159 @@
+pub fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+
+    a
+}
+
+pub fn lcm(a: u64, b: u64) -> u64 {
+    if a == 0 || b == 0 {
+        return 0;
+    }
+
+    a / gcd(a, b) * b
+}
+
+/// The lowest common multiple of every value, or 1 if there are none
+pub fn lcm_all(values: impl IntoIterator<Item = u64>) -> u64 {
+    values.into_iter().fold(1, lcm)
+}
+
+/// Returns x, y, z such that:
+///
+/// a * x + b * y = gcd(a, b) = z
+pub fn extended_gcd(a: i64, b: i64) -> (i64, i64, i64) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_s, mut s) = (1, 0);
+    let (mut old_t, mut t) = (0, 1);
+
+    while r != 0 {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        (old_s, s) = (s, old_s - q * s);
+        (old_t, t) = (t, old_t - q * t);
+    }
+
+    (old_s, old_t, old_r)
+}
+
+/// Calculates `base.pow(exponent) % modulus` without overflowing
+pub fn mod_pow(base: u64, mut exponent: u64, modulus: u64) -> u64 {
+    if modulus == 1 {
+        return 0;
+    }
+
+    let modulus = modulus as u128;
+    let mut base = base as u128 % modulus;
+    let mut result = 1;
+
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = result * base % modulus;
+        }
+
+        base = base * base % modulus;
+        exponent >>= 1;
+    }
+
+    result as u64
+}
+
+/// Returns x in `0..modulus` such that `a * x = 1 (mod modulus)`, or `None`
+/// if `a` and `modulus` aren't coprime.
+pub fn mod_inverse(a: i64, modulus: i64) -> Option<i64> {
+    let (x, _, g) = extended_gcd(a.rem_euclid(modulus), modulus);
+    (g == 1).then(|| x.rem_euclid(modulus))
+}
+
+/// Solves a system of congruences `x = remainder (mod modulus)` using the
+/// Chinese Remainder Theorem, for example to find when several loops with
+/// different offsets all line up.
+///
+/// The moduli don't need to be coprime. Returns the smallest non-negative
+/// solution along with the lcm of the moduli (every solution differs by a
+/// multiple of it), or `None` if the congruences are inconsistent.
+pub fn chinese_remainder(congruences: impl IntoIterator<Item = (i64, i64)>) -> Option<(i64, i64)> {
+    let mut result: i128 = 0;
+    let mut combined_modulus: i128 = 1;
+
+    for (remainder, modulus) in congruences {
+        assert!(modulus > 0, "Moduli must be positive");
+
+        let modulus = modulus as i128;
+        let remainder = (remainder as i128).rem_euclid(modulus);
+
+        // We need result + combined_modulus * k = remainder (mod modulus), which
+        // is solvable iff the difference is a multiple of their gcd
+        let combined = i64::try_from(combined_modulus).expect("CRT modulus overflowed i64");
+        let (p, _, g) = extended_gcd(combined, modulus as i64);
+        let (p, g) = (p as i128, g as i128);
+
+        let difference = remainder - result;
+        if difference % g != 0 {
+            return None;
+        }
+
+        let step = modulus / g;
+        let k = (difference / g % step * p).rem_euclid(step);
+
+        result += combined_modulus * k;
+        combined_modulus *= step;
+        result = result.rem_euclid(combined_modulus);
+    }
+
+    Some((
+        result.try_into().expect("CRT solution overflowed i64"),
+        combined_modulus
+            .try_into()
+            .expect("CRT modulus overflowed i64"),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gcd_test() {
+        assert_eq!(gcd(5, 7), 1);
+        assert_eq!(gcd(15, 21), 3);
+    }
+
+    #[test]
+    fn lcm_test() {
+        assert_eq!(lcm(4, 6), 12);
+        assert_eq!(lcm(0, 6), 0);
+        assert_eq!(lcm_all([3, 4, 10]), 60);
+    }
+
+    #[test]
+    fn extended_gcd_test() {
+        let (x, y, z) = extended_gcd(240, 46);
+
+        assert_eq!(x * 240 + y * 46, z);
+        assert_eq!(z, gcd(240, 46) as i64);
+    }
+
+    #[test]
+    fn mod_pow_and_inverse() {
+        assert_eq!(mod_pow(4, 13, 497), 445);
+        assert_eq!(mod_pow(u64::MAX, u64::MAX, 1_000_000_007), 254_368_884);
+
+        assert_eq!(mod_inverse(3, 11), Some(4));
+        assert_eq!(mod_inverse(-3, 11), Some(7));
+        assert_eq!(mod_inverse(6, 9), None);
+    }
+
+    #[test]
+    fn crt_coprime() {
+        assert_eq!(chinese_remainder([(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+    }
+
+    #[test]
+    fn crt_non_coprime() {
+        assert_eq!(chinese_remainder([(2, 4), (4, 6)]), Some((10, 12)));
+        assert_eq!(chinese_remainder([(1, 4), (2, 6)]), None);
+        assert_eq!(chinese_remainder([]), Some((0, 1)));
+    }
+}
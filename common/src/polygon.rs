@@ -0,0 +1,168 @@
+use crate::{gcd, Rational};
+
+/// A simple polygon with every vertex on an integer lattice point, such as a
+/// loop of pipes or a dig plan.
+///
+/// The edge from the last vertex back to the first is implied. Vertices are
+/// `(x, y)` pairs of `i64`, so that large polygons like part 2 dig plans fit.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LatticePolygon {
+    vertices: Vec<(i64, i64)>,
+}
+
+impl LatticePolygon {
+    pub fn new(mut vertices: Vec<(i64, i64)>) -> Self {
+        if vertices.len() > 1 && vertices.first() == vertices.last() {
+            vertices.pop();
+        }
+
+        Self { vertices }
+    }
+
+    /// Follows a path of `((dx, dy), distance)` steps from the origin, where
+    /// `(dx, dy)` is the unit step in the direction to go
+    pub fn from_steps(steps: impl IntoIterator<Item = ((i64, i64), i64)>) -> Self {
+        let mut position = (0, 0);
+        let mut vertices = vec![position];
+
+        for ((dx, dy), distance) in steps {
+            position = (position.0 + dx * distance, position.1 + dy * distance);
+            vertices.push(position);
+        }
+
+        Self::new(vertices)
+    }
+
+    pub fn vertices(&self) -> &[(i64, i64)] {
+        &self.vertices
+    }
+
+    fn edges(&self) -> impl Iterator<Item = ((i64, i64), (i64, i64))> + '_ {
+        self.vertices
+            .iter()
+            .copied()
+            .zip(self.vertices.iter().copied().cycle().skip(1))
+    }
+
+    /// Twice the enclosed area, which is always an integer
+    pub fn double_area(&self) -> i64 {
+        // shoelace formula
+        self.edges()
+            .map(|((x1, y1), (x2, y2))| x1 * y2 - x2 * y1)
+            .sum::<i64>()
+            .abs()
+    }
+
+    pub fn area(&self) -> Rational {
+        Rational::new(self.double_area().into(), 2)
+    }
+
+    /// The number of lattice points lying on the edges
+    pub fn boundary_points(&self) -> i64 {
+        self.edges()
+            .map(|((x1, y1), (x2, y2))| gcd(x1.abs_diff(x2), y1.abs_diff(y2)) as i64)
+            .sum()
+    }
+
+    /// The number of lattice points strictly inside the polygon
+    pub fn interior_points(&self) -> i64 {
+        // Pick's theorem: A = i + b/2 - 1
+        (self.double_area() - self.boundary_points() + 2) / 2
+    }
+
+    pub fn is_on_boundary(&self, point: (i64, i64)) -> bool {
+        let (px, py) = point;
+
+        self.edges().any(|((x1, y1), (x2, y2))| {
+            let cross = (x2 - x1) * (py - y1) - (y2 - y1) * (px - x1);
+            cross == 0
+                && (x1.min(x2)..=x1.max(x2)).contains(&px)
+                && (y1.min(y2)..=y1.max(y2)).contains(&py)
+        })
+    }
+
+    /// Whether the point is strictly inside the polygon
+    pub fn is_inside(&self, point: (i64, i64)) -> bool {
+        if self.is_on_boundary(point) {
+            return false;
+        }
+
+        let (px, py) = point;
+
+        // count the edges crossed by a ray going in the +x direction, counting
+        // vertices as slightly above the ray so they're never counted twice
+        self.edges()
+            .filter(|&((x1, y1), (x2, y2))| {
+                if (y1 > py) == (y2 > py) {
+                    return false;
+                }
+
+                // px < x1 + (py - y1) * (x2 - x1) / (y2 - y1), without dividing
+                let lhs = (px - x1) * (y2 - y1);
+                let rhs = (py - y1) * (x2 - x1);
+                if y2 > y1 {
+                    lhs < rhs
+                } else {
+                    lhs > rhs
+                }
+            })
+            .count()
+            % 2
+            == 1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn area_and_points() {
+        // a 4x3 rectangle of lattice points
+        let rectangle = LatticePolygon::new(vec![(0, 0), (3, 0), (3, 2), (0, 2)]);
+
+        assert_eq!(rectangle.area(), Rational::integer(6));
+        assert_eq!(rectangle.boundary_points(), 10);
+        assert_eq!(rectangle.interior_points(), 2);
+
+        let triangle = LatticePolygon::new(vec![(0, 0), (3, 0), (0, 3), (0, 0)]);
+        assert_eq!(triangle.vertices().len(), 3);
+        assert_eq!(triangle.area(), Rational::new(9, 2));
+        assert_eq!(triangle.interior_points(), 1);
+    }
+
+    #[test]
+    fn from_steps() {
+        const NORTH: (i64, i64) = (0, -1);
+        const EAST: (i64, i64) = (1, 0);
+        const SOUTH: (i64, i64) = (0, 1);
+        const WEST: (i64, i64) = (-1, 0);
+
+        // an L shape made of a 5x2 and a 3x2 block of lattice points
+        let polygon = LatticePolygon::from_steps([
+            (EAST, 4),
+            (SOUTH, 1),
+            (WEST, 2),
+            (SOUTH, 2),
+            (WEST, 2),
+            (NORTH, 3),
+        ]);
+
+        assert_eq!(polygon.double_area(), 16);
+        assert_eq!(polygon.boundary_points(), 14);
+        assert_eq!(polygon.interior_points() + polygon.boundary_points(), 16);
+    }
+
+    #[test]
+    fn point_in_polygon() {
+        let polygon = LatticePolygon::new(vec![(0, 0), (4, 0), (4, 4), (2, 2), (0, 4)]);
+
+        assert!(polygon.is_inside((1, 1)));
+        assert!(polygon.is_inside((3, 2)));
+        assert!(!polygon.is_inside((2, 3)));
+        assert!(!polygon.is_inside((4, 2)));
+        assert!(polygon.is_on_boundary((4, 2)));
+        assert!(polygon.is_on_boundary((1, 3)));
+        assert!(!polygon.is_inside((5, 0)));
+    }
+}
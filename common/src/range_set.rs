@@ -0,0 +1,304 @@
+use std::ops::{Add, Mul, Range, RangeInclusive, Sub};
+
+/// Integer types which can be stored in a [`RangeSet`]
+pub trait RangeValue:
+    Copy + Ord + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self>
+{
+    const ZERO: Self;
+    const ONE: Self;
+    const MAX: Self;
+}
+
+macro_rules! impl_range_value {
+    ($($t:ty),*) => {
+        $(
+            impl RangeValue for $t {
+                const ZERO: Self = 0;
+                const ONE: Self = 1;
+                const MAX: Self = <$t>::MAX;
+            }
+        )*
+    };
+}
+
+impl_range_value!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+/// A set of integers stored as a sorted list of disjoint half-open ranges.
+///
+/// Touching or overlapping ranges are merged as they are inserted, so each
+/// range in [`RangeSet::ranges`] is separated from the next by a gap.
+///
+/// Since the ranges are half-open, the largest value of `T` can't be in the set.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct RangeSet<T> {
+    ranges: Vec<Range<T>>,
+}
+
+impl<T> Default for RangeSet<T> {
+    fn default() -> Self {
+        Self { ranges: vec![] }
+    }
+}
+
+impl<T: RangeValue> RangeSet<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, range: Range<T>) {
+        if range.is_empty() {
+            return;
+        }
+
+        // find every existing range which overlaps or touches the new one, and
+        // replace them all with a single merged range
+        let first = self.ranges.partition_point(|r| r.end < range.start);
+        let last = self.ranges.partition_point(|r| r.start <= range.end);
+
+        let merged = if first < last {
+            range.start.min(self.ranges[first].start)..range.end.max(self.ranges[last - 1].end)
+        } else {
+            range
+        };
+
+        self.ranges.splice(first..last, [merged]);
+    }
+
+    /// Panics if the range ends at `T::MAX`, which has no half-open end
+    pub fn insert_inclusive(&mut self, range: RangeInclusive<T>) {
+        let (start, end) = range.into_inner();
+        if end < start {
+            return;
+        }
+
+        assert!(
+            end != T::MAX,
+            "A RangeSet can't contain the maximum value of its type"
+        );
+        self.insert(start..end + T::ONE);
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        let index = self.ranges.partition_point(|r| r.end <= value);
+        self.ranges
+            .get(index)
+            .is_some_and(|range| range.contains(&value))
+    }
+
+    /// The disjoint ranges making up the set, in ascending order
+    pub fn ranges(&self) -> &[Range<T>] {
+        &self.ranges
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// The number of values in the set
+    pub fn total_length(&self) -> T {
+        self.ranges
+            .iter()
+            .fold(T::ZERO, |total, range| total + (range.end - range.start))
+    }
+
+    pub fn min(&self) -> Option<T> {
+        self.ranges.first().map(|range| range.start)
+    }
+
+    pub fn max(&self) -> Option<T> {
+        self.ranges.last().map(|range| range.end - T::ONE)
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        let mut result = self.clone();
+        for range in &other.ranges {
+            result.insert(range.clone());
+        }
+
+        result
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut ranges = vec![];
+        let (mut i, mut j) = (0, 0);
+
+        while i < self.ranges.len() && j < other.ranges.len() {
+            let (a, b) = (&self.ranges[i], &other.ranges[j]);
+
+            let overlap = a.start.max(b.start)..a.end.min(b.end);
+            if !overlap.is_empty() {
+                ranges.push(overlap);
+            }
+
+            if a.end < b.end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+
+        Self { ranges }
+    }
+
+    /// Every value in `self` which isn't in `other`
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut ranges = vec![];
+        let mut j = 0;
+
+        for range in &self.ranges {
+            let mut start = range.start;
+
+            while j < other.ranges.len() && other.ranges[j].end <= start {
+                j += 1;
+            }
+
+            let mut k = j;
+            while k < other.ranges.len() && other.ranges[k].start < range.end {
+                if other.ranges[k].start > start {
+                    ranges.push(start..other.ranges[k].start);
+                }
+
+                start = start.max(other.ranges[k].end);
+                k += 1;
+            }
+
+            if start < range.end {
+                ranges.push(start..range.end);
+            }
+        }
+
+        Self { ranges }
+    }
+
+    /// Splits into the values less than `at`, and the values greater than or equal to it
+    pub fn split_at(&self, at: T) -> (Self, Self) {
+        let mut below = vec![];
+        let mut above = vec![];
+
+        for range in &self.ranges {
+            if range.end <= at {
+                below.push(range.clone());
+            } else if range.start >= at {
+                above.push(range.clone());
+            } else {
+                below.push(range.start..at);
+                above.push(at..range.end);
+            }
+        }
+
+        (Self { ranges: below }, Self { ranges: above })
+    }
+
+    /// Maps each value through a piecewise linear function. Each piece is a
+    /// source range along with where the start of that range should end up.
+    /// Values not covered by any piece are left where they are.
+    ///
+    /// The source ranges of the pieces shouldn't overlap each other.
+    pub fn map_piecewise<'a>(&self, pieces: impl IntoIterator<Item = &'a (Range<T>, T)>) -> Self
+    where
+        T: 'a,
+    {
+        let mut result = Self::new();
+        let mut unmapped = self.clone();
+
+        for (source, destination) in pieces {
+            let mut piece = Self::new();
+            piece.insert(source.clone());
+
+            for range in self.intersection(&piece).ranges {
+                result.insert(
+                    *destination + (range.start - source.start)
+                        ..*destination + (range.end - source.start),
+                );
+            }
+
+            unmapped = unmapped.difference(&piece);
+        }
+
+        result.union(&unmapped)
+    }
+}
+
+impl<T: RangeValue> FromIterator<Range<T>> for RangeSet<T> {
+    fn from_iter<I: IntoIterator<Item = Range<T>>>(iter: I) -> Self {
+        let mut set = Self::new();
+        for range in iter {
+            set.insert(range);
+        }
+
+        set
+    }
+}
+
+impl<T: RangeValue> FromIterator<RangeInclusive<T>> for RangeSet<T> {
+    fn from_iter<I: IntoIterator<Item = RangeInclusive<T>>>(iter: I) -> Self {
+        let mut set = Self::new();
+        for range in iter {
+            set.insert_inclusive(range);
+        }
+
+        set
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::single_range_in_vec_init)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn insert_merges_ranges() {
+        let set: RangeSet<u64> = [3..=5, 10..=14, 16..=20, 12..=18].into_iter().collect();
+
+        assert_eq!(set.ranges(), &[3..6, 10..21]);
+        assert_eq!(set.total_length(), 14);
+        assert!(set.contains(5));
+        assert!(!set.contains(6));
+        assert_eq!((set.min(), set.max()), (Some(3), Some(20)));
+
+        let touching: RangeSet<i32> = [0..2, 4..6, 2..4].into_iter().collect();
+        assert_eq!(touching.ranges(), &[0..6]);
+    }
+
+    #[test]
+    fn inclusive_ranges_up_to_the_maximum() {
+        let mut set = RangeSet::new();
+        set.insert_inclusive(250u8..=254);
+
+        assert_eq!(set.ranges(), &[250..255]);
+        assert_eq!(set.max(), Some(254));
+    }
+
+    #[test]
+    #[should_panic = "can't contain the maximum value"]
+    fn inclusive_range_ending_at_the_maximum() {
+        RangeSet::new().insert_inclusive(250u8..=u8::MAX);
+    }
+
+    #[test]
+    fn set_operations() {
+        let a: RangeSet<i32> = [0..10, 20..30].into_iter().collect();
+        let b: RangeSet<i32> = [5..25].into_iter().collect();
+
+        assert_eq!(a.union(&b).ranges(), &[0..30]);
+        assert_eq!(a.intersection(&b).ranges(), &[5..10, 20..25]);
+        assert_eq!(a.difference(&b).ranges(), &[0..5, 25..30]);
+        assert_eq!(b.difference(&a).ranges(), &[10..20]);
+
+        let (below, above) = a.split_at(25);
+        assert_eq!(below.ranges(), &[0..10, 20..25]);
+        assert_eq!(above.ranges(), &[25..30]);
+    }
+
+    #[test]
+    fn map_piecewise() {
+        // the seed-to-soil map from 2023 day 5
+        let seeds: RangeSet<usize> = [79..93, 55..68].into_iter().collect();
+        let soil = seeds.map_piecewise(&[(98..100, 50), (50..98, 52)]);
+
+        assert_eq!(soil.ranges(), &[57..70, 81..95]);
+
+        let unmapped = seeds.map_piecewise(&[(0..10, 100)]);
+        assert_eq!(unmapped, seeds);
+    }
+}
@@ -0,0 +1,571 @@
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+};
+
+pub type Rgb = [u8; 3];
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Frame {
+    /// An index into the palette for each cell, row by row
+    pixels: Vec<u8>,
+    /// How long to show the frame for, in hundredths of a second
+    delay: u16,
+}
+
+/// Captures the state of a grid simulation as it runs, and writes the frames
+/// out as an animated GIF or PNG so the simulation can be watched afterwards.
+///
+/// Each cell of the grid becomes a `scale × scale` square of pixels. Frames
+/// which are the same as the one before are merged into it, so it's fine to
+/// capture after every step even if most steps don't change anything.
+///
+/// There can be at most 256 different colours across the whole animation.
+#[derive(Clone, Debug)]
+pub struct Recorder {
+    pub scale: usize,
+    /// How long to show each frame for, in hundredths of a second
+    pub delay: u16,
+    width: usize,
+    height: usize,
+    palette: Vec<Rgb>,
+    colour_indices: HashMap<Rgb, u8>,
+    frames: Vec<Frame>,
+}
+
+impl Recorder {
+    pub fn new(scale: usize, delay: u16) -> Self {
+        assert!(scale > 0, "Scale must be at least 1");
+
+        Self {
+            scale,
+            delay,
+            width: 0,
+            height: 0,
+            palette: vec![],
+            colour_indices: HashMap::new(),
+            frames: vec![],
+        }
+    }
+
+    pub fn frame_count(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    fn colour_index(&mut self, colour: Rgb) -> u8 {
+        if let Some(&index) = self.colour_indices.get(&colour) {
+            return index;
+        }
+
+        assert!(
+            self.palette.len() < 256,
+            "Can't record more than 256 different colours"
+        );
+
+        let index = self.palette.len() as u8;
+        self.palette.push(colour);
+        self.colour_indices.insert(colour, index);
+        index
+    }
+
+    /// Adds a frame `width` cells across and `height` cells down, where `pixel`
+    /// gives the colour of the cell at `(x, y)`
+    pub fn capture(&mut self, width: usize, height: usize, pixel: impl Fn(usize, usize) -> Rgb) {
+        if self.frames.is_empty() {
+            self.width = width;
+            self.height = height;
+        }
+
+        assert!(
+            (width, height) == (self.width, self.height),
+            "Every frame must be the same size"
+        );
+
+        let mut pixels = Vec::with_capacity(width * height);
+        for y in 0..height {
+            for x in 0..width {
+                let index = self.colour_index(pixel(x, y));
+                pixels.push(index);
+            }
+        }
+
+        match self.frames.last_mut() {
+            Some(last) if last.pixels == pixels => {
+                last.delay = last.delay.saturating_add(self.delay)
+            }
+            _ => self.frames.push(Frame {
+                pixels,
+                delay: self.delay,
+            }),
+        }
+    }
+
+    /// Captures a frame from rows of cells, such as the points of a grid
+    pub fn capture_rows<T>(&mut self, rows: &[Vec<T>], colour: impl Fn(&T) -> Rgb) {
+        let width = rows.first().map_or(0, Vec::len);
+        self.capture(width, rows.len(), |x, y| colour(&rows[y][x]));
+    }
+
+    /// The palette indices of a frame once it's been scaled up, row by row
+    fn scaled_rows<'a>(&'a self, frame: &'a Frame) -> impl Iterator<Item = Vec<u8>> + 'a {
+        frame.pixels.chunks(self.width.max(1)).flat_map(move |row| {
+            let scaled: Vec<u8> = row
+                .iter()
+                .flat_map(|&index| std::iter::repeat_n(index, self.scale))
+                .collect();
+
+            std::iter::repeat_n(scaled, self.scale)
+        })
+    }
+
+    fn image_size(&self) -> io::Result<(u16, u16)> {
+        let size = |cells: usize| {
+            u16::try_from(cells * self.scale)
+                .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "Frames are too big"))
+        };
+
+        Ok((size(self.width)?, size(self.height)?))
+    }
+
+    /// Writes the animation as a GIF which loops forever
+    pub fn write_gif(&self, mut writer: impl Write) -> io::Result<()> {
+        let (width, height) = self.image_size()?;
+
+        // the colour table has to have a power of 2 entries, and at least 2
+        let bits = (self.palette.len().max(2) as u32)
+            .next_power_of_two()
+            .trailing_zeros();
+        let bits = bits.max(1);
+
+        writer.write_all(b"GIF89a")?;
+        writer.write_all(&width.to_le_bytes())?;
+        writer.write_all(&height.to_le_bytes())?;
+        writer.write_all(&[0x80 | ((bits as u8 - 1) << 4) | (bits as u8 - 1), 0, 0])?;
+
+        for index in 0..1 << bits {
+            writer.write_all(self.palette.get(index).unwrap_or(&[0, 0, 0]))?;
+        }
+
+        writer.write_all(b"\x21\xff\x0bNETSCAPE2.0\x03\x01\x00\x00\x00")?;
+
+        let minimum_code_size = bits.max(2) as u8;
+        for frame in &self.frames {
+            // graphic control extension, for the delay
+            writer.write_all(&[0x21, 0xf9, 0x04, 0x04])?;
+            writer.write_all(&frame.delay.to_le_bytes())?;
+            writer.write_all(&[0, 0])?;
+
+            // image descriptor covering the whole screen
+            writer.write_all(&[0x2c, 0, 0, 0, 0])?;
+            writer.write_all(&width.to_le_bytes())?;
+            writer.write_all(&height.to_le_bytes())?;
+            writer.write_all(&[0])?;
+
+            let pixels: Vec<u8> = self.scaled_rows(frame).flatten().collect();
+            let data = lzw_encode(&pixels, minimum_code_size);
+
+            writer.write_all(&[minimum_code_size])?;
+            for block in data.chunks(255) {
+                writer.write_all(&[block.len() as u8])?;
+                writer.write_all(block)?;
+            }
+            writer.write_all(&[0])?;
+        }
+
+        writer.write_all(&[0x3b])
+    }
+
+    /// Writes the animation as an animated PNG which loops forever. The image
+    /// data isn't compressed, so prefer GIF for long recordings.
+    pub fn write_apng(&self, mut writer: impl Write) -> io::Result<()> {
+        let (width, height) = self.image_size()?;
+        let (width, height) = (u32::from(width), u32::from(height));
+
+        writer.write_all(b"\x89PNG\r\n\x1a\n")?;
+
+        let mut header = vec![];
+        header.extend(width.to_be_bytes());
+        header.extend(height.to_be_bytes());
+        // 8 bit palette indices, default compression, filtering and no interlacing
+        header.extend([8, 3, 0, 0, 0]);
+        write_chunk(&mut writer, b"IHDR", &header)?;
+
+        let mut animation_control = vec![];
+        animation_control.extend((self.frames.len() as u32).to_be_bytes());
+        animation_control.extend(0u32.to_be_bytes());
+        write_chunk(&mut writer, b"acTL", &animation_control)?;
+
+        let palette: Vec<u8> = if self.palette.is_empty() {
+            vec![0, 0, 0]
+        } else {
+            self.palette.concat()
+        };
+        write_chunk(&mut writer, b"PLTE", &palette)?;
+
+        let mut sequence_number = 0u32;
+        for (i, frame) in self.frames.iter().enumerate() {
+            let mut frame_control = vec![];
+            frame_control.extend(sequence_number.to_be_bytes());
+            frame_control.extend(width.to_be_bytes());
+            frame_control.extend(height.to_be_bytes());
+            frame_control.extend(0u32.to_be_bytes());
+            frame_control.extend(0u32.to_be_bytes());
+            frame_control.extend(frame.delay.to_be_bytes());
+            frame_control.extend(100u16.to_be_bytes());
+            // don't dispose of or blend with the previous frame
+            frame_control.extend([0, 0]);
+            write_chunk(&mut writer, b"fcTL", &frame_control)?;
+            sequence_number += 1;
+
+            // every row starts with its filter type, which is 0 for no filter
+            let scanlines: Vec<u8> = self
+                .scaled_rows(frame)
+                .flat_map(|row| std::iter::once(0).chain(row))
+                .collect();
+            let data = zlib_stored(&scanlines);
+
+            // the first frame doubles as the still image for viewers which
+            // don't support animation
+            if i == 0 {
+                write_chunk(&mut writer, b"IDAT", &data)?;
+            } else {
+                let mut frame_data = sequence_number.to_be_bytes().to_vec();
+                frame_data.extend(data);
+                write_chunk(&mut writer, b"fdAT", &frame_data)?;
+                sequence_number += 1;
+            }
+        }
+
+        write_chunk(&mut writer, b"IEND", &[])
+    }
+
+    /// Writes the animation to `path`, as a GIF if it ends in `.gif` and as an
+    /// animated PNG otherwise
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let path = path.as_ref();
+        let writer = BufWriter::new(File::create(path)?);
+
+        if path.extension().is_some_and(|extension| extension == "gif") {
+            self.write_gif(writer)
+        } else {
+            self.write_apng(writer)
+        }
+    }
+}
+
+/// Packs variable width codes into bytes, least significant bit first
+struct BitWriter {
+    bytes: Vec<u8>,
+    current: u32,
+    bit_count: u32,
+}
+
+impl BitWriter {
+    fn write(&mut self, code: u16, width: u32) {
+        self.current |= u32::from(code) << self.bit_count;
+        self.bit_count += width;
+
+        while self.bit_count >= 8 {
+            self.bytes.push(self.current as u8);
+            self.current >>= 8;
+            self.bit_count -= 8;
+        }
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.bit_count > 0 {
+            self.bytes.push(self.current as u8);
+        }
+
+        self.bytes
+    }
+}
+
+/// Compresses palette indices with the variable width LZW used by GIF
+fn lzw_encode(pixels: &[u8], minimum_code_size: u8) -> Vec<u8> {
+    const MAX_CODE: u16 = 4095;
+
+    let clear = 1u16 << minimum_code_size;
+    let end = clear + 1;
+
+    let mut output = BitWriter {
+        bytes: vec![],
+        current: 0,
+        bit_count: 0,
+    };
+    let mut table: HashMap<(u16, u8), u16> = HashMap::new();
+    let mut code_size = u32::from(minimum_code_size) + 1;
+    let mut next_code = end + 1;
+
+    output.write(clear, code_size);
+
+    let Some((&first, rest)) = pixels.split_first() else {
+        output.write(end, code_size);
+        return output.finish();
+    };
+
+    let mut prefix = u16::from(first);
+    for &pixel in rest {
+        if let Some(&code) = table.get(&(prefix, pixel)) {
+            prefix = code;
+            continue;
+        }
+
+        output.write(prefix, code_size);
+
+        if next_code > MAX_CODE {
+            // the table is full, so start again
+            output.write(clear, code_size);
+            table.clear();
+            code_size = u32::from(minimum_code_size) + 1;
+            next_code = end + 1;
+        } else {
+            table.insert((prefix, pixel), next_code);
+            // the decoder adds its entries one code behind us, so it only needs
+            // the wider codes once the code we just added could be sent
+            if next_code == 1 << code_size && code_size < 12 {
+                code_size += 1;
+            }
+            next_code += 1;
+        }
+
+        prefix = u16::from(pixel);
+    }
+
+    output.write(prefix, code_size);
+    // the decoder adds one more entry after the last code, which might take
+    // it over to wider codes
+    if next_code == 1 << code_size && code_size < 12 {
+        code_size += 1;
+    }
+    output.write(end, code_size);
+
+    output.finish()
+}
+
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in bytes {
+        crc ^= u32::from(byte);
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xedb8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+
+    !crc
+}
+
+fn adler32(bytes: &[u8]) -> u32 {
+    let (a, b) = bytes.iter().fold((1u32, 0u32), |(a, b), &byte| {
+        let a = (a + u32::from(byte)) % 65521;
+        (a, (b + a) % 65521)
+    });
+
+    (b << 16) | a
+}
+
+/// Wraps `data` in a zlib stream made of uncompressed deflate blocks
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    let mut output = vec![0x78, 0x01];
+
+    let mut blocks = data.chunks(u16::MAX as usize).peekable();
+    if blocks.peek().is_none() {
+        output.extend([1, 0, 0, 0xff, 0xff]);
+    }
+
+    while let Some(block) = blocks.next() {
+        let is_last = blocks.peek().is_none();
+        let length = block.len() as u16;
+
+        output.push(u8::from(is_last));
+        output.extend(length.to_le_bytes());
+        output.extend((!length).to_le_bytes());
+        output.extend(block);
+    }
+
+    output.extend(adler32(data).to_be_bytes());
+    output
+}
+
+fn write_chunk(writer: &mut impl Write, kind: &[u8; 4], data: &[u8]) -> io::Result<()> {
+    writer.write_all(&(data.len() as u32).to_be_bytes())?;
+
+    let mut checked = kind.to_vec();
+    checked.extend(data);
+    writer.write_all(&checked)?;
+    writer.write_all(&crc32(&checked).to_be_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BLACK: Rgb = [0, 0, 0];
+    const WHITE: Rgb = [255, 255, 255];
+
+    /// A straightforward GIF LZW decoder to check the encoder against
+    fn lzw_decode(data: &[u8], minimum_code_size: u8) -> Vec<u8> {
+        let clear = 1usize << minimum_code_size;
+        let end = clear + 1;
+
+        let mut bits = data
+            .iter()
+            .flat_map(|&byte| (0..8).map(move |bit| (byte >> bit) & 1));
+        let mut read = |width: u32| -> usize {
+            (0..width).fold(0, |code, bit| {
+                code | (usize::from(bits.next().unwrap()) << bit)
+            })
+        };
+
+        let mut output = vec![];
+        let mut table: Vec<Vec<u8>> = vec![];
+        let mut code_size = u32::from(minimum_code_size) + 1;
+        let mut previous: Option<Vec<u8>> = None;
+
+        loop {
+            let code = read(code_size);
+
+            if code == clear {
+                table = (0..clear).map(|i| vec![i as u8]).collect();
+                table.extend([vec![], vec![]]);
+                code_size = u32::from(minimum_code_size) + 1;
+                previous = None;
+                continue;
+            }
+
+            if code == end {
+                return output;
+            }
+
+            let entry = match (table.get(code), &previous) {
+                (Some(entry), _) => entry.clone(),
+                (None, Some(previous)) => {
+                    let mut entry = previous.clone();
+                    entry.push(previous[0]);
+                    entry
+                }
+                (None, None) => panic!("Unknown code {code}"),
+            };
+
+            if let Some(mut previous) = previous {
+                if table.len() < 4096 {
+                    previous.push(entry[0]);
+                    table.push(previous);
+                }
+            }
+
+            if table.len() == 1 << code_size && code_size < 12 {
+                code_size += 1;
+            }
+
+            output.extend(&entry);
+            previous = Some(entry);
+        }
+    }
+
+    #[test]
+    fn lzw_round_trip() {
+        // a repetitive image with a long run, and enough noise to fill the table
+        let mut pixels = vec![0; 1000];
+        let mut state = 12345u32;
+        for _ in 0..20_000 {
+            state = state.wrapping_mul(1_103_515_245).wrapping_add(12345);
+            pixels.push((state >> 16) as u8 % 4);
+        }
+
+        for minimum_code_size in [2, 3, 8] {
+            let encoded = lzw_encode(&pixels, minimum_code_size);
+            assert_eq!(lzw_decode(&encoded, minimum_code_size), pixels);
+        }
+
+        assert_eq!(lzw_decode(&lzw_encode(&[], 2), 2), vec![]);
+    }
+
+    #[test]
+    fn merges_repeated_frames() {
+        let mut rows = vec![vec![false; 2]; 2];
+        let mut recorder = Recorder::new(1, 10);
+
+        recorder.capture_rows(&rows, |&on| if on { WHITE } else { BLACK });
+        recorder.capture_rows(&rows, |&on| if on { WHITE } else { BLACK });
+        rows[1][0] = true;
+        recorder.capture_rows(&rows, |&on| if on { WHITE } else { BLACK });
+
+        assert_eq!(recorder.frame_count(), 2);
+        assert_eq!(recorder.frames[0].delay, 20);
+        assert_eq!(recorder.frames[1].pixels, vec![0, 0, 1, 0]);
+        assert_eq!(recorder.palette, vec![BLACK, WHITE]);
+    }
+
+    #[test]
+    fn gif_structure() {
+        let mut recorder = Recorder::new(2, 5);
+        recorder.capture(3, 1, |x, _| if x == 1 { WHITE } else { BLACK });
+
+        let mut gif = vec![];
+        recorder.write_gif(&mut gif).unwrap();
+
+        assert_eq!(&gif[..13], b"GIF89a\x06\x00\x02\x00\x80\x00\x00");
+        assert_eq!(&gif[13..19], &[0, 0, 0, 255, 255, 255]);
+        assert_eq!(gif.last(), Some(&0x3b));
+
+        // find the image data after the image descriptor
+        let start = gif.iter().position(|&byte| byte == 0x2c).unwrap() + 10;
+        let minimum_code_size = gif[start];
+        let length = gif[start + 1] as usize;
+        let pixels = lzw_decode(&gif[start + 2..start + 2 + length], minimum_code_size);
+        assert_eq!(pixels, vec![0, 0, 1, 1, 0, 0, 0, 0, 1, 1, 0, 0]);
+    }
+
+    #[test]
+    fn apng_structure() {
+        let mut recorder = Recorder::new(1, 5);
+        recorder.capture(2, 1, |x, _| if x == 0 { WHITE } else { BLACK });
+        recorder.capture(2, 1, |_, _| BLACK);
+
+        let mut png = vec![];
+        recorder.write_apng(&mut png).unwrap();
+        assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+
+        let mut chunks = vec![];
+        let mut rest = &png[8..];
+        while !rest.is_empty() {
+            let length = u32::from_be_bytes(rest[..4].try_into().unwrap()) as usize;
+            let (kind, data) = (&rest[4..8], &rest[8..8 + length]);
+            let crc = u32::from_be_bytes(rest[8 + length..12 + length].try_into().unwrap());
+
+            assert_eq!(crc, crc32(&rest[4..8 + length]));
+            chunks.push((std::str::from_utf8(kind).unwrap(), data.to_vec()));
+            rest = &rest[12 + length..];
+        }
+
+        let kinds: Vec<_> = chunks.iter().map(|(kind, _)| *kind).collect();
+        assert_eq!(
+            kinds,
+            ["IHDR", "acTL", "PLTE", "fcTL", "IDAT", "fcTL", "fdAT", "IEND"]
+        );
+        assert_eq!(chunks[1].1, [0, 0, 0, 2, 0, 0, 0, 0]);
+        assert_eq!(chunks[2].1, [255, 255, 255, 0, 0, 0]);
+
+        // a single stored block holding the filter byte then the two pixels
+        assert_eq!(chunks[4].1[2..10], [1, 3, 0, 0xfc, 0xff, 0, 0, 1]);
+        assert_eq!(&chunks[6].1[..4], &[0, 0, 0, 2]);
+        assert_eq!(chunks[6].1[6..12], [1, 3, 0, 0xfc, 0xff, 0]);
+    }
+
+    #[test]
+    fn checksums() {
+        assert_eq!(crc32(b"IEND"), 0xae42_6082);
+        assert_eq!(adler32(b"Wikipedia"), 0x11e6_0398);
+    }
+}
@@ -0,0 +1,181 @@
+use std::{
+    cmp::{Ordering, Reverse},
+    collections::{BinaryHeap, HashSet},
+    hash::Hash,
+};
+
+use crate::Graph;
+
+impl Graph {
+    /// Orders the nodes so that every edge goes from an earlier node to a later
+    /// one, using Kahn's algorithm. Ties are broken by the smallest index first
+    /// so the result is deterministic.
+    ///
+    /// If the graph contains a cycle, returns the nodes of one such cycle in
+    /// order instead.
+    pub fn topological_sort(&self) -> Result<Vec<usize>, Vec<usize>> {
+        debug_assert!(self.is_directed(), "can only sort a directed graph");
+
+        kahn(self.len(), |node| self.neighbours(node).collect())
+    }
+}
+
+/// A partial order given by a set of `(before, after)` rules, such as the page
+/// ordering rules in 2024 day 5.
+///
+/// Items with no rule between them are considered equal.
+#[derive(Clone, Debug)]
+pub struct OrderingRules<T> {
+    rules: HashSet<(T, T)>,
+}
+
+impl<T> OrderingRules<T>
+where
+    T: Hash + Eq + Clone,
+{
+    pub fn new(rules: impl IntoIterator<Item = (T, T)>) -> Self {
+        Self {
+            rules: rules.into_iter().collect(),
+        }
+    }
+
+    pub fn compare(&self, a: &T, b: &T) -> Ordering {
+        if self.rules.contains(&(a.clone(), b.clone())) {
+            Ordering::Less
+        } else if self.rules.contains(&(b.clone(), a.clone())) {
+            Ordering::Greater
+        } else {
+            Ordering::Equal
+        }
+    }
+
+    /// Whether no item in the list is required to be before an item
+    /// earlier in the list
+    pub fn is_sorted(&self, items: &[T]) -> bool {
+        items.iter().enumerate().all(|(i, a)| {
+            items[i + 1..]
+                .iter()
+                .all(|b| self.compare(a, b) != Ordering::Greater)
+        })
+    }
+
+    /// Sorts the items so that every rule between them is satisfied, keeping
+    /// unrelated items in their original order where possible.
+    ///
+    /// The rules don't need to be transitive or total, but if they contain a
+    /// cycle among these items then that cycle is returned instead.
+    pub fn sorted(&self, items: &[T]) -> Result<Vec<T>, Vec<T>> {
+        let successors = |i: usize| {
+            (0..items.len())
+                .filter(|&j| self.compare(&items[i], &items[j]) == Ordering::Less)
+                .collect()
+        };
+
+        let to_items =
+            |indices: Vec<usize>| indices.into_iter().map(|i| items[i].clone()).collect();
+        kahn(items.len(), successors)
+            .map(to_items)
+            .map_err(to_items)
+    }
+}
+
+fn kahn(len: usize, successors: impl Fn(usize) -> Vec<usize>) -> Result<Vec<usize>, Vec<usize>> {
+    let successors: Vec<_> = (0..len).map(successors).collect();
+
+    let mut in_degree = vec![0; len];
+    for &next in successors.iter().flatten() {
+        in_degree[next] += 1;
+    }
+
+    let mut ready: BinaryHeap<_> = (0..len)
+        .filter(|&node| in_degree[node] == 0)
+        .map(Reverse)
+        .collect();
+    let mut order = Vec::with_capacity(len);
+
+    while let Some(Reverse(node)) = ready.pop() {
+        order.push(node);
+
+        for &next in &successors[node] {
+            in_degree[next] -= 1;
+            if in_degree[next] == 0 {
+                ready.push(Reverse(next));
+            }
+        }
+    }
+
+    if order.len() == len {
+        return Ok(order);
+    }
+
+    // Everything left over is either on a cycle or downstream of one, and every
+    // leftover node has a leftover predecessor. So walking backwards along
+    // leftover edges must eventually loop.
+    let mut predecessor = vec![None; len];
+    for node in 0..len {
+        for &next in &successors[node] {
+            if in_degree[node] > 0 && in_degree[next] > 0 {
+                predecessor[next] = Some(node);
+            }
+        }
+    }
+
+    let mut seen = vec![false; len];
+    let mut current = (0..len).find(|&node| in_degree[node] > 0).unwrap();
+    while !seen[current] {
+        seen[current] = true;
+        current = predecessor[current].unwrap();
+    }
+
+    let mut cycle = vec![current];
+    let mut node = predecessor[current].unwrap();
+    while node != current {
+        cycle.push(node);
+        node = predecessor[node].unwrap();
+    }
+
+    cycle.reverse();
+    Err(cycle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sorts_graph() {
+        let graph = Graph::parse_arrows(
+            "shirt -> tie, belt\ntie -> jacket\nbelt -> jacket\nsocks -> shoes",
+        );
+
+        let order = graph.topological_sort().unwrap();
+        let position = |name| order.iter().position(|&n| graph.name(n) == name).unwrap();
+
+        assert_eq!(order.len(), 6);
+        assert!(position("shirt") < position("tie"));
+        assert!(position("belt") < position("jacket"));
+        assert!(position("socks") < position("shoes"));
+    }
+
+    #[test]
+    fn reports_cycles() {
+        let graph = Graph::parse_arrows("a -> b\nb -> c\nc -> d, b");
+        let cycle = graph.topological_sort().unwrap_err();
+
+        let mut names: Vec<_> = cycle.iter().map(|&n| graph.name(n)).collect();
+        names.sort();
+        assert_eq!(names, &["b", "c"]);
+    }
+
+    #[test]
+    fn ordering_rules() {
+        let rules = OrderingRules::new([(97, 75), (75, 47), (97, 47), (47, 13)]);
+
+        assert!(rules.is_sorted(&[97, 75, 47]));
+        assert!(!rules.is_sorted(&[75, 97, 47]));
+        assert_eq!(rules.sorted(&[13, 75, 97]), Ok(vec![13, 97, 75]));
+
+        let rules = OrderingRules::new([(1, 2), (2, 3), (3, 1)]);
+        assert!(rules.sorted(&[1, 2, 3]).is_err());
+    }
+}
@@ -0,0 +1,152 @@
+/// A disjoint-set forest over the values `0..len`, with path compression and
+/// union by size.
+#[derive(Clone, Debug)]
+pub struct UnionFind {
+    parent: Vec<usize>,
+    size: Vec<usize>,
+    components: usize,
+}
+
+impl UnionFind {
+    /// Creates `len` singleton sets
+    pub fn new(len: usize) -> Self {
+        Self {
+            parent: (0..len).collect(),
+            size: vec![1; len],
+            components: len,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.parent.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parent.is_empty()
+    }
+
+    /// The representative of the set containing `value`
+    pub fn find(&mut self, value: usize) -> usize {
+        let mut root = value;
+        while self.parent[root] != root {
+            root = self.parent[root];
+        }
+
+        let mut current = value;
+        while self.parent[current] != root {
+            let next = self.parent[current];
+            self.parent[current] = root;
+            current = next;
+        }
+
+        root
+    }
+
+    /// Merges the sets containing `a` and `b`. Returns false if they were
+    /// already in the same set.
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (mut a, mut b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+
+        if self.size[a] < self.size[b] {
+            (a, b) = (b, a);
+        }
+
+        self.parent[b] = a;
+        self.size[a] += self.size[b];
+        self.components -= 1;
+        true
+    }
+
+    pub fn connected(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    /// The number of values in the same set as `value`
+    pub fn component_size(&mut self, value: usize) -> usize {
+        let root = self.find(value);
+        self.size[root]
+    }
+
+    /// The number of disjoint sets
+    pub fn components(&self) -> usize {
+        self.components
+    }
+
+    /// The size of every disjoint set, in no particular order
+    pub fn component_sizes(&self) -> Vec<usize> {
+        (0..self.len())
+            .filter(|&value| self.parent[value] == value)
+            .map(|root| self.size[root])
+            .collect()
+    }
+
+    /// Unions every pair in turn, returning the ones which joined two
+    /// previously separate sets. Given pairs sorted by weight, this is Kruskal's
+    /// algorithm and the result is a minimum spanning forest.
+    pub fn union_all(
+        &mut self,
+        pairs: impl IntoIterator<Item = (usize, usize)>,
+    ) -> Vec<(usize, usize)> {
+        pairs
+            .into_iter()
+            .filter(|&(a, b)| self.union(a, b))
+            .collect()
+    }
+
+    /// Unions pairs in order until everything is in one set, and returns the
+    /// pair which completed it. Returns `None` if the pairs run out first.
+    pub fn connect_until_single(
+        &mut self,
+        pairs: impl IntoIterator<Item = (usize, usize)>,
+    ) -> Option<(usize, usize)> {
+        if self.components <= 1 {
+            return None;
+        }
+
+        pairs.into_iter().find(|&(a, b)| {
+            self.union(a, b);
+            self.components == 1
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn union_and_find() {
+        let mut sets = UnionFind::new(6);
+
+        assert!(sets.union(0, 1));
+        assert!(sets.union(2, 3));
+        assert!(sets.union(1, 3));
+        assert!(!sets.union(0, 2));
+
+        assert!(sets.connected(0, 3));
+        assert!(!sets.connected(0, 4));
+        assert_eq!(sets.component_size(2), 4);
+        assert_eq!(sets.components(), 3);
+
+        let mut sizes = sets.component_sizes();
+        sizes.sort();
+        assert_eq!(sizes, &[1, 1, 4]);
+    }
+
+    #[test]
+    fn kruskal_helpers() {
+        let mut sets = UnionFind::new(4);
+
+        let joined = sets.union_all([(0, 1), (1, 0), (2, 3)]);
+        assert_eq!(joined, &[(0, 1), (2, 3)]);
+
+        assert_eq!(
+            sets.connect_until_single([(0, 1), (1, 2), (0, 3)]),
+            Some((1, 2))
+        );
+        assert_eq!(sets.components(), 1);
+    }
+}